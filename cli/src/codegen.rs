@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 pub struct Idl {
     pub instructions: Vec<Instruction>,
     pub accounts: Vec<AccountType>,
+    #[serde(default)]
+    pub events: Vec<EventType>,
     pub types: Vec<TypeDef>,
 }
 
@@ -29,7 +31,23 @@ pub struct Account {
 pub struct Arg {
     pub name: String,
     #[serde(rename = "type")]
-    pub type_: String,
+    pub type_: IdlType,
+}
+
+// IDL types are either a plain string ("u64") or an object wrapping another type
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum IdlType {
+    Primitive(String),
+    Option { option: Box<IdlType> },
+    Vec { vec: Box<IdlType> },
+    Array { array: (Box<IdlType>, usize) },
+    Defined { defined: DefinedType },
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct DefinedType {
+    pub name: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -38,9 +56,41 @@ pub struct AccountType {
     pub discriminator: Vec<u8>,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EventType {
+    pub name: String,
+    pub discriminator: Vec<u8>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct TypeDef {
     pub name: String,
+    #[serde(rename = "type")]
+    pub type_: TypeDefKind,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum TypeDefKind {
+    Struct {
+        #[serde(default)]
+        fields: Vec<Field>,
+    },
+    Enum {
+        variants: Vec<EnumVariant>,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Field {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: IdlType,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EnumVariant {
+    pub name: String,
 }
 
 pub fn generate_code(idl: &Idl) -> String {
@@ -48,17 +98,104 @@ pub fn generate_code(idl: &Idl) -> String {
     output.push_str("// Auto-generated from IDL - DO NOT EDIT\n");
     output.push_str("// Generated by build.rs from target/idl/solana_token.json\n\n");
     output.push_str("#![allow(dead_code)]\n\n");
-    output.push_str("use anchor_lang::{AnchorSerialize, Discriminator, InstructionData, ToAccountMetas};\n");
+    output.push_str("use anchor_lang::{AnchorDeserialize, AnchorSerialize, Discriminator, InstructionData, ToAccountMetas};\n");
     output.push_str("use anchor_client::solana_sdk::{instruction::AccountMeta, pubkey::Pubkey};\n\n");
 
+    // Generate custom types used by instruction args and events
+    generate_types(&mut output, idl);
+
     // Generate code for each instruction
     for instruction in &idl.instructions {
         generate_instruction(&mut output, instruction);
     }
 
+    // Generate event decoders
+    if !idl.events.is_empty() {
+        generate_events(&mut output, &idl.events, &idl.types);
+    }
+
     output
 }
 
+pub fn generate_types(output: &mut String, idl: &Idl) {
+    // Account and event structs are also listed under `types`; only emit the rest
+    let is_custom = |name: &str| {
+        !idl.accounts.iter().any(|a| a.name == name) && !idl.events.iter().any(|e| e.name == name)
+    };
+
+    for type_def in idl.types.iter().filter(|t| is_custom(&t.name)) {
+        output.push_str("#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, PartialEq)]\n");
+        match &type_def.type_ {
+            TypeDefKind::Struct { fields } => {
+                output.push_str(&format!("pub struct {} {{\n", type_def.name));
+                for field in fields {
+                    output.push_str(&format!("    pub {}: {},\n",
+                        field.name, rust_type(&field.type_)));
+                }
+            }
+            TypeDefKind::Enum { variants } => {
+                output.push_str(&format!("pub enum {} {{\n", type_def.name));
+                for variant in variants {
+                    output.push_str(&format!("    {},\n", variant.name));
+                }
+            }
+        }
+        output.push_str("}\n\n");
+    }
+}
+
+pub fn generate_events(output: &mut String, events: &[EventType], types: &[TypeDef]) {
+    output.push_str("pub mod events {\n");
+    output.push_str("    use super::*;\n\n");
+
+    // Generate a struct and Discriminator impl for each event
+    for event in events {
+        output.push_str("    #[derive(AnchorDeserialize, Debug, Clone, PartialEq)]\n");
+        output.push_str(&format!("    pub struct {} {{\n", event.name));
+        let fields = types.iter().find(|t| t.name == event.name).map(|t| &t.type_);
+        if let Some(TypeDefKind::Struct { fields }) = fields {
+            for field in fields {
+                output.push_str(&format!("        pub {}: {},\n",
+                    field.name, rust_type(&field.type_)));
+            }
+        }
+        output.push_str("    }\n\n");
+
+        output.push_str(&format!("    impl Discriminator for {} {{\n", event.name));
+        let disc_bytes = event.discriminator.iter()
+            .map(|b| b.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        output.push_str(&format!("        const DISCRIMINATOR: &'static [u8] = &[{}];\n", disc_bytes));
+        output.push_str("    }\n\n");
+    }
+
+    // Generate an enum covering every event
+    output.push_str("    #[derive(Debug, Clone, PartialEq)]\n");
+    output.push_str("    pub enum Event {\n");
+    for event in events {
+        output.push_str(&format!("        {}({}),\n", event.name, event.name));
+    }
+    output.push_str("    }\n\n");
+
+    // Generate decoder for `Program data:` log payloads (discriminator + borsh body)
+    output.push_str("    pub fn decode(data: &[u8]) -> Option<Event> {\n");
+    output.push_str("        if data.len() < 8 {\n");
+    output.push_str("            return None;\n");
+    output.push_str("        }\n");
+    output.push_str("        let (discriminator, mut body) = data.split_at(8);\n");
+    for event in events {
+        output.push_str(&format!("        if discriminator == {}::DISCRIMINATOR {{\n", event.name));
+        output.push_str(&format!("            return {}::deserialize(&mut body).ok().map(Event::{});\n",
+            event.name, event.name));
+        output.push_str("        }\n");
+    }
+    output.push_str("        None\n");
+    output.push_str("    }\n");
+
+    output.push_str("}\n\n");
+}
+
 pub fn generate_instruction(output: &mut String, instruction: &Instruction) {
     let module_name = to_snake_case(&instruction.name);
     let struct_name = to_pascal_case(&instruction.name);
@@ -70,7 +207,7 @@ pub fn generate_instruction(output: &mut String, instruction: &Instruction) {
     output.push_str("    #[derive(AnchorSerialize)]\n");
     output.push_str(&format!("    pub struct {} {{\n", struct_name));
     for arg in &instruction.args {
-        let rust_type = map_idl_type(&arg.type_);
+        let rust_type = rust_type(&arg.type_);
         output.push_str(&format!("        pub {}: {},\n", arg.name, rust_type));
     }
     output.push_str("    }\n\n");
@@ -162,6 +299,18 @@ pub fn map_idl_type(idl_type: &str) -> &str {
     }
 }
 
+pub fn rust_type(idl_type: &IdlType) -> String {
+    match idl_type {
+        IdlType::Primitive(name) => map_idl_type(name).to_string(),
+        IdlType::Option { option } => format!("Option<{}>", rust_type(option)),
+        IdlType::Vec { vec } => format!("Vec<{}>", rust_type(vec)),
+        IdlType::Array {
+            array: (element, len),
+        } => format!("[{}; {}]", rust_type(element), len),
+        IdlType::Defined { defined } => defined.name.clone(), // Pass through for custom types
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,6 +340,30 @@ mod tests {
         assert_eq!(map_idl_type("CustomType"), "CustomType");
     }
 
+    #[test]
+    fn test_rust_type() {
+        let option: IdlType = serde_json::from_str(r#"{"option": "pubkey"}"#).unwrap();
+        assert_eq!(rust_type(&option), "Option<Pubkey>");
+
+        let nested: IdlType = serde_json::from_str(r#"{"option": {"option": "u64"}}"#).unwrap();
+        assert_eq!(rust_type(&nested), "Option<Option<u64>>");
+
+        let vec: IdlType = serde_json::from_str(r#"{"vec": "string"}"#).unwrap();
+        assert_eq!(rust_type(&vec), "Vec<String>");
+
+        let array: IdlType = serde_json::from_str(r#"{"array": ["u8", 32]}"#).unwrap();
+        assert_eq!(rust_type(&array), "[u8; 32]");
+
+        let proof: IdlType = serde_json::from_str(r#"{"vec": {"array": ["u8", 32]}}"#).unwrap();
+        assert_eq!(rust_type(&proof), "Vec<[u8; 32]>");
+
+        assert_eq!(rust_type(&IdlType::Primitive("u8".to_string())), "u8");
+
+        let defined: IdlType =
+            serde_json::from_str(r#"{"defined": {"name": "AccountState"}}"#).unwrap();
+        assert_eq!(rust_type(&defined), "AccountState");
+    }
+
     #[test]
     fn test_idl_parsing() {
         let idl_json = r#"{
@@ -219,7 +392,97 @@ mod tests {
         assert_eq!(idl.instructions[0].accounts.len(), 2);
         assert_eq!(idl.instructions[0].args.len(), 1);
         assert_eq!(idl.instructions[0].args[0].name, "decimals");
-        assert_eq!(idl.instructions[0].args[0].type_, "u8");
+        assert_eq!(idl.instructions[0].args[0].type_, IdlType::Primitive("u8".to_string()));
+    }
+
+    #[test]
+    fn test_event_code_generation() {
+        let idl_json = r#"{
+            "instructions": [],
+            "accounts": [],
+            "events": [
+                {"name": "TokensMinted", "discriminator": [1, 2, 3, 4, 5, 6, 7, 8]}
+            ],
+            "types": [
+                {
+                    "name": "TokensMinted",
+                    "type": {
+                        "kind": "struct",
+                        "fields": [
+                            {"name": "mint", "type": "pubkey"},
+                            {"name": "amount", "type": "u64"},
+                            {"name": "previous", "type": {"option": "pubkey"}}
+                        ]
+                    }
+                },
+                {
+                    "name": "AccountState",
+                    "type": {
+                        "kind": "enum",
+                        "variants": [{"name": "Initialized"}, {"name": "Frozen"}]
+                    }
+                }
+            ]
+        }"#;
+
+        let idl: Idl = serde_json::from_str(idl_json).expect("Failed to parse test IDL");
+        let output = generate_code(&idl);
+
+        assert!(output.contains("pub mod events"));
+        assert!(output.contains("pub struct TokensMinted"));
+        assert!(output.contains("pub mint: Pubkey"));
+        assert!(output.contains("pub amount: u64"));
+        assert!(output.contains("pub previous: Option<Pubkey>"));
+        assert!(output.contains("impl Discriminator for TokensMinted"));
+        assert!(output.contains("[1, 2, 3, 4, 5, 6, 7, 8]"));
+        assert!(output.contains("TokensMinted(TokensMinted)"));
+        assert!(output.contains("pub fn decode(data: &[u8]) -> Option<Event>"));
+    }
+
+    #[test]
+    fn test_type_code_generation() {
+        let idl_json = r#"{
+            "instructions": [],
+            "accounts": [{"name": "TokenMint", "discriminator": [1, 2, 3, 4, 5, 6, 7, 8]}],
+            "types": [
+                {
+                    "name": "TokenMint",
+                    "type": {"kind": "struct", "fields": [{"name": "decimals", "type": "u8"}]}
+                },
+                {
+                    "name": "MetadataField",
+                    "type": {
+                        "kind": "struct",
+                        "fields": [
+                            {"name": "key", "type": "string"},
+                            {"name": "value", "type": "string"}
+                        ]
+                    }
+                },
+                {
+                    "name": "AccountState",
+                    "type": {
+                        "kind": "enum",
+                        "variants": [{"name": "Initialized"}, {"name": "Frozen"}]
+                    }
+                }
+            ]
+        }"#;
+
+        let idl: Idl = serde_json::from_str(idl_json).expect("Failed to parse test IDL");
+        let output = generate_code(&idl);
+
+        assert!(output.contains("pub struct MetadataField {\n    pub key: String,\n    pub value: String,\n}"));
+        assert!(output.contains("pub enum AccountState {\n    Initialized,\n    Frozen,\n}"));
+        assert!(!output.contains("pub struct TokenMint"));
+    }
+
+    #[test]
+    fn test_no_events_module_without_events() {
+        let idl: Idl = serde_json::from_str(r#"{"instructions": [], "accounts": [], "types": []}"#)
+            .expect("Failed to parse test IDL");
+
+        assert!(!generate_code(&idl).contains("pub mod events"));
     }
 
     #[test]
@@ -242,7 +505,7 @@ mod tests {
             args: vec![
                 Arg {
                     name: "amount".to_string(),
-                    type_: "u64".to_string(),
+                    type_: IdlType::Primitive("u64".to_string()),
                 },
             ],
         };
@@ -4,14 +4,32 @@ pub mod generated;
 use anchor_client::{
     anchor_lang::{declare_id, InstructionData, ToAccountMetas},
     solana_sdk::{
-        instruction::Instruction,
-        signature::{Keypair, Signer},
+        ed25519_instruction::new_ed25519_instruction_with_signature,
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        message::Message,
+        packet::PACKET_DATA_SIZE,
+        pubkey::Pubkey,
+        signature::{Keypair, Signature, Signer},
+        sysvar,
     },
-    Program,
+    ClientError, Program,
 };
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
 use solana_system_interface::program as system_program;
-use std::{fs, rc::Rc};
+use solana_token::{
+    airdrop_leaf, merkle_parent, permit_message, AccountState, ClaimBitmap, DenylistEntry,
+    MinterRole, Offer, PendingMint, TokenAccount, TokenMetadata, TokenMint, VestingAccount,
+    CLAIMS_PER_BITMAP,
+};
+use std::{
+    fs,
+    rc::Rc,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 declare_id!("48WQW8ZMQKJhV1FKnGrYVDMEoqc8XutQmvKuqcmRrKux");
 
@@ -24,31 +42,192 @@ pub fn load_keypair(path: &str) -> Result<Keypair> {
     Ok(keypair)
 }
 
+pub fn parse_pubkey(value: &str) -> Result<Pubkey> {
+    Pubkey::from_str(value).with_context(|| format!("Invalid public key: {}", value))
+}
+
+pub fn token_account_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"token", owner.as_ref(), mint.as_ref()], &ID).0
+}
+
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"metadata", mint.as_ref()], &ID).0
+}
+
+pub fn denylist_address(mint: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"deny", mint.as_ref(), wallet.as_ref()], &ID).0
+}
+
+pub fn minter_role_address(mint: &Pubkey, minter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"minter", mint.as_ref(), minter.as_ref()], &ID).0
+}
+
+pub fn permit_nonce_address(owner: &Pubkey, mint: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"permit",
+            owner.as_ref(),
+            mint.as_ref(),
+            &nonce.to_le_bytes(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+pub fn distributor_address(mint: &Pubkey, merkle_root: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[b"distributor", mint.as_ref(), merkle_root], &ID).0
+}
+
+pub fn claim_bitmap_address(distributor: &Pubkey, chunk: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"claims", distributor.as_ref(), &chunk.to_le_bytes()],
+        &ID,
+    )
+    .0
+}
+
+pub fn offer_address(maker: &Pubkey, id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"offer", maker.as_ref(), &id.to_le_bytes()], &ID).0
+}
+
+pub fn vesting_address(mint: &Pubkey, id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"vesting", mint.as_ref(), &id.to_le_bytes()], &ID).0
+}
+
+pub fn pending_mint_address(mint: &Pubkey, id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"pending_mint", mint.as_ref(), &id.to_le_bytes()], &ID).0
+}
+
+/// Generates a keypair and saves it as `<prefix>-<address>.json` in the current directory.
+fn generate_keypair_file(prefix: &str) -> Result<Keypair> {
+    let keypair = Keypair::new();
+    let path = format!("{}-{}.json", prefix, keypair.pubkey());
+
+    let keypair_bytes = keypair.to_bytes();
+    fs::write(&path, serde_json::to_string(&keypair_bytes.to_vec())?)
+        .with_context(|| format!("Failed to write {} keypair to disk", prefix))?;
+
+    println!("Generated new {} keypair: {}", prefix, path);
+    Ok(keypair)
+}
+
+/// Current unix time from the local clock, which may drift slightly from the cluster's.
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64)
+}
+
+/// The mint, freeze or owner authority an instruction is sent as: either the payer
+/// itself or a multisig, approved by the member keypairs in `signers`.
+pub struct Authority {
+    pub address: Pubkey,
+    pub signers: Vec<Keypair>,
+}
+
+impl Authority {
+    /// The payer acting as its own authority; it already signs every transaction.
+    pub fn payer(payer: &Keypair) -> Self {
+        Authority {
+            address: payer.pubkey(),
+            signers: Vec::new(),
+        }
+    }
+
+    pub fn multisig(address: Pubkey, signers: Vec<Keypair>) -> Self {
+        Authority { address, signers }
+    }
+
+    /// The multisig members, passed as signing accounts after all other accounts.
+    pub fn signer_metas(&self) -> Vec<AccountMeta> {
+        self.signers
+            .iter()
+            .map(|signer| AccountMeta::new_readonly(signer.pubkey(), true))
+            .collect()
+    }
+}
+
+/// Sends `instructions` in one transaction, with the multisig members of `authority`
+/// appended to each instruction's accounts and signing alongside the payer.
+fn send_as_authority(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    instructions: Vec<Instruction>,
+) -> Result<Signature, ClientError> {
+    let mut request = program.request();
+    for mut instruction in instructions {
+        instruction.accounts.extend(authority.signer_metas());
+        request = request.instruction(instruction);
+    }
+    for signer in &authority.signers {
+        request = request.signer(signer);
+    }
+    request.send()
+}
+
+/// Optional settings for a new mint; everything defaults to off.
+#[derive(Debug, Default)]
+pub struct MintOptions {
+    /// Path to the mint keypair (a new one is generated if not provided)
+    pub mint_keypair: Option<String>,
+    pub freeze_authority: Option<Pubkey>,
+    pub max_supply: Option<u64>,
+    pub pause_authority: Option<Pubkey>,
+    pub fee_authority: Option<Pubkey>,
+    pub non_transferable: bool,
+    pub permanent_delegate: Option<Pubkey>,
+    /// New token accounts start frozen until the freeze authority thaws them
+    pub default_frozen: bool,
+    pub metadata: Option<MetadataArgs>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MetadataArgs {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
 pub fn init(
     program: &Program<Rc<Keypair>>,
     payer: &Keypair,
     decimals: u8,
-    mint_keypair: Option<String>,
+    options: MintOptions,
 ) -> Result<()> {
+    let MintOptions {
+        mint_keypair,
+        freeze_authority,
+        max_supply,
+        pause_authority,
+        fee_authority,
+        non_transferable,
+        permanent_delegate,
+        default_frozen,
+        metadata,
+    } = options;
+
     // Load or generate mint keypair
     let mint = match mint_keypair {
         Some(path) => load_keypair(&path).context("Failed to load mint keypair")?,
-        None => {
-            let keypair = Keypair::new();
-            let path = format!("mint-{}.json", keypair.pubkey());
-
-            // Save keypair to disk
-            let keypair_bytes = keypair.to_bytes();
-            fs::write(&path, serde_json::to_string(&keypair_bytes.to_vec())?)
-                .context("Failed to write mint keypair to disk")?;
-
-            println!("Generated new mint keypair: {}", path);
-            keypair
-        }
+        None => generate_keypair_file("mint")?,
     };
 
     // Build initialize instruction using generated code
-    let initialize = generated::initialize::Initialize { decimals };
+    let initialize = generated::initialize::Initialize {
+        decimals,
+        freeze_authority,
+        max_supply,
+        pause_authority,
+        fee_authority,
+        non_transferable,
+        permanent_delegate,
+        default_account_state: if default_frozen {
+            generated::AccountState::Frozen
+        } else {
+            generated::AccountState::Initialized
+        },
+    };
     let accounts = generated::initialize::Accounts {
         mint: mint.pubkey(),
         authority: payer.pubkey(),
@@ -62,10 +241,30 @@ pub fn init(
         data: initialize.data(),
     };
 
+    // Metadata is created in the same transaction so the mint never exists without it
+    let mut request = program.request().instruction(instruction);
+    if let Some(metadata) = &metadata {
+        let initialize_metadata = generated::initialize_metadata::InitializeMetadata {
+            name: metadata.name.clone(),
+            symbol: metadata.symbol.clone(),
+            uri: metadata.uri.clone(),
+        };
+        let accounts = generated::initialize_metadata::Accounts {
+            mint: mint.pubkey(),
+            metadata: metadata_address(&mint.pubkey()),
+            authority: payer.pubkey(),
+            payer: payer.pubkey(),
+            system_program: system_program::ID.to_bytes().into(),
+        };
+        request = request.instruction(Instruction {
+            program_id: ID,
+            accounts: accounts.to_account_metas(None),
+            data: initialize_metadata.data(),
+        });
+    }
+
     // Send transaction with mint as additional signer
-    let signature = program
-        .request()
-        .instruction(instruction)
+    let signature = request
         .signer(&mint)
         .send()
         .context("Failed to send initialize transaction")?;
@@ -74,34 +273,2745 @@ pub fn init(
     println!("✓ Token mint initialized");
     println!("  Mint address: {}", mint.pubkey());
     println!("  Decimals: {}", decimals);
+    if let Some(freeze_authority) = freeze_authority {
+        println!("  Freeze authority: {}", freeze_authority);
+    }
+    if let Some(max_supply) = max_supply {
+        println!("  Max supply: {}", max_supply);
+    }
+    if let Some(pause_authority) = pause_authority {
+        println!("  Pause authority: {}", pause_authority);
+    }
+    if let Some(fee_authority) = fee_authority {
+        println!("  Fee authority: {}", fee_authority);
+    }
+    if non_transferable {
+        println!("  Non-transferable: true");
+    }
+    if let Some(permanent_delegate) = permanent_delegate {
+        println!("  Permanent delegate: {}", permanent_delegate);
+    }
+    if default_frozen {
+        println!("  New accounts start frozen");
+    }
+    if let Some(metadata) = metadata {
+        println!("  Name: {}", metadata.name);
+        println!("  Symbol: {}", metadata.symbol);
+        println!("  URI: {}", metadata.uri);
+    }
     println!("  Transaction: {}", signature);
 
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::io::Write;
-    use tempfile::NamedTempFile;
+pub fn mint_info(program: &Program<Rc<Keypair>>, mint: Pubkey) -> Result<()> {
+    let mint_account: TokenMint = program
+        .account(mint)
+        .context("Failed to fetch mint account")?;
 
-    #[test]
-    fn test_load_keypair_valid() {
-        let keypair = Keypair::new();
-        let keypair_bytes = keypair.to_bytes();
-        let json = serde_json::to_string(&keypair_bytes.to_vec()).unwrap();
+    println!("Mint: {}", mint);
+    println!("  Authority: {}", format_optional(mint_account.authority));
+    if let Some(pending_authority) = mint_account.pending_authority {
+        println!("  Pending authority: {}", pending_authority);
+    }
+    println!(
+        "  Freeze authority: {}",
+        format_optional(mint_account.freeze_authority)
+    );
+    println!(
+        "  Pause authority: {}",
+        format_optional(mint_account.pause_authority)
+    );
+    println!("  Paused: {}", mint_account.paused);
+    println!("  Decimals: {}", mint_account.decimals);
+    println!("  Total supply: {}", mint_account.total_supply);
+    match mint_account.max_supply {
+        Some(max_supply) => println!(
+            "  Max supply: {} ({} remaining)",
+            max_supply,
+            max_supply.saturating_sub(mint_account.total_supply)
+        ),
+        None => println!("  Max supply: uncapped"),
+    }
+    println!(
+        "  Fee authority: {}",
+        format_optional(mint_account.fee_authority)
+    );
+    println!(
+        "  Transfer fee: {} basis points (max {})",
+        mint_account.transfer_fee_basis_points, mint_account.maximum_fee
+    );
+    println!("  Withheld fees: {}", mint_account.withheld_amount);
+    println!("  Non-transferable: {}", mint_account.non_transferable);
+    println!(
+        "  Permanent delegate: {}",
+        format_optional(mint_account.permanent_delegate)
+    );
+    println!(
+        "  Default account state: {}",
+        match mint_account.default_account_state {
+            AccountState::Initialized => "initialized",
+            AccountState::Frozen => "frozen",
+        }
+    );
+    println!("  Mint delay: {} seconds", mint_account.mint_delay);
+    if let Some(delay) = mint_account.pending_mint_delay {
+        println!(
+            "  Pending mint delay: {} seconds, applicable after {}",
+            delay, mint_account.mint_delay_change_after
+        );
+    }
+    match mint_account.mint_rate_limit {
+        Some(limit) => println!(
+            "  Mint rate limit: {} per {} seconds ({} available now)",
+            limit,
+            mint_account.mint_rate_window,
+            limit.saturating_sub(mint_account.mint_capacity_used(unix_now()))
+        ),
+        None => println!("  Mint rate limit: unlimited"),
+    }
 
-        let mut temp_file = NamedTempFile::new().unwrap();
-        temp_file.write_all(json.as_bytes()).unwrap();
-        temp_file.flush().unwrap();
+    // The mint is the first field after the 8-byte discriminator of minter roles
+    // and queued mints.
+    let filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        8,
+        mint.as_ref(),
+    ))];
+    let minters = program
+        .accounts::<MinterRole>(filters.clone())
+        .context("Failed to fetch minters")?;
+    for (_, minter_role) in minters {
+        println!(
+            "  Minter: {} (allowance {})",
+            minter_role.minter, minter_role.allowance
+        );
+    }
+    let mut pending_mints = program
+        .accounts::<PendingMint>(filters)
+        .context("Failed to fetch queued mints")?;
+    pending_mints.sort_by_key(|(_, pending_mint)| pending_mint.id);
+    for (_, pending_mint) in pending_mints {
+        println!(
+            "  Queued mint #{}: {} to {} (executable after unix time {})",
+            pending_mint.id,
+            pending_mint.amount,
+            pending_mint.token_account,
+            pending_mint.execute_after
+        );
+    }
 
-        let loaded = load_keypair(temp_file.path().to_str().unwrap()).unwrap();
-        assert_eq!(loaded.pubkey(), keypair.pubkey());
+    // Metadata is optional, so a missing account is not an error
+    if let Ok(metadata) = program.account::<TokenMetadata>(metadata_address(&mint)) {
+        println!("  Name: {}", metadata.name);
+        println!("  Symbol: {}", metadata.symbol);
+        println!("  URI: {}", metadata.uri);
+        println!(
+            "  Metadata update authority: {}",
+            format_optional(metadata.update_authority)
+        );
+        for field in metadata.additional_metadata {
+            println!("  {}: {}", field.key, field.value);
+        }
     }
 
-    #[test]
-    fn test_load_keypair_invalid_path() {
-        let result = load_keypair("/nonexistent/path/keypair.json");
-        assert!(result.is_err());
+    Ok(())
+}
+
+fn format_optional<T: ToString>(value: Option<T>) -> String {
+    value
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| "none".to_string())
+}
+
+pub fn mint_tokens(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+    as_minter: bool,
+) -> Result<()> {
+    let mint_tokens = generated::mint_tokens::MintTokens { amount };
+    let accounts = generated::mint_tokens::Accounts {
+        mint,
+        token_account: token_account_address(&owner, &mint),
+        authority: authority.address,
+        deny_entry: denylist_address(&mint, &owner),
+        minter_role: minter_role_address(&mint, &authority.address),
+    };
+
+    let mut account_metas = accounts.to_account_metas(None);
+    if !as_minter {
+        // Anchor reads the program id in an optional account's place as `None`.
+        *account_metas.last_mut().unwrap() = AccountMeta::new_readonly(ID, false);
+    }
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: mint_tokens.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send mint_tokens transaction")?;
+
+    println!("✓ Minted {} to {}", amount, owner);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+const MINT_RECIPIENTS_PER_TX: usize = 10;
+
+pub fn mint_to_many(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    recipients: &[(Pubkey, u64)],
+) -> Result<()> {
+    for chunk in recipients.chunks(MINT_RECIPIENTS_PER_TX) {
+        let mint_to_many = generated::mint_to_many::MintToMany {
+            amounts: chunk.iter().map(|(_, amount)| *amount).collect(),
+        };
+        let accounts = generated::mint_to_many::Accounts {
+            mint,
+            authority: authority.address,
+        };
+
+        // Each recipient is passed as its token account followed by its denylist entry
+        let mut account_metas = accounts.to_account_metas(None);
+        for (owner, _) in chunk {
+            account_metas.push(AccountMeta::new(token_account_address(owner, &mint), false));
+            account_metas.push(AccountMeta::new_readonly(
+                denylist_address(&mint, owner),
+                false,
+            ));
+        }
+
+        let instruction = Instruction {
+            program_id: ID,
+            accounts: account_metas,
+            data: mint_to_many.data(),
+        };
+
+        let signature = send_as_authority(program, authority, vec![instruction])
+            .context("Failed to send mint_to_many transaction")?;
+
+        for (owner, amount) in chunk {
+            println!("✓ Minted {} to {}", amount, owner);
+        }
+        println!("  Transaction: {}", signature);
+    }
+
+    let total: u64 = recipients.iter().map(|(_, amount)| amount).sum();
+    println!(
+        "✓ Minted {} tokens to {} recipients",
+        total,
+        recipients.len()
+    );
+
+    Ok(())
+}
+
+fn transfer_many_instruction(
+    authority: &Authority,
+    mint: Pubkey,
+    recipients: &[(Pubkey, u64)],
+) -> Instruction {
+    let transfer_many = generated::transfer_many::TransferMany {
+        amounts: recipients.iter().map(|(_, amount)| *amount).collect(),
+    };
+    let accounts = generated::transfer_many::Accounts {
+        mint,
+        from: token_account_address(&authority.address, &mint),
+        authority: authority.address,
+        from_deny_entry: denylist_address(&mint, &authority.address),
+    };
+
+    // Each recipient is passed as its token account followed by its denylist entry
+    let mut account_metas = accounts.to_account_metas(None);
+    for (owner, _) in recipients {
+        account_metas.push(AccountMeta::new(token_account_address(owner, &mint), false));
+        account_metas.push(AccountMeta::new_readonly(
+            denylist_address(&mint, owner),
+            false,
+        ));
+    }
+
+    Instruction {
+        program_id: ID,
+        accounts: account_metas,
+        data: transfer_many.data(),
+    }
+}
+
+/// Whether a transaction of `instructions` paid by `payer`, signatures included,
+/// fits in a single packet.
+fn fits_in_transaction(payer: &Pubkey, instructions: &[Instruction]) -> bool {
+    let message = Message::new(instructions, Some(payer));
+    let signatures = message.header.num_required_signatures as usize;
+
+    // A one-byte signature count, the signatures, then the message itself.
+    1 + signatures * 64 + message.serialize().len() <= PACKET_DATA_SIZE
+}
+
+/// Splits `recipients` into the largest batches that fit in one transaction. With two
+/// accounts per recipient that is 12 when the payer sends alone, fewer when multisig
+/// members sign as well.
+fn transfer_many_batches<'a>(
+    payer: &Pubkey,
+    authority: &Authority,
+    mint: Pubkey,
+    recipients: &'a [(Pubkey, u64)],
+) -> Vec<&'a [(Pubkey, u64)]> {
+    let fits = |batch: &[(Pubkey, u64)]| {
+        let mut instruction = transfer_many_instruction(authority, mint, batch);
+        instruction.accounts.extend(authority.signer_metas());
+        fits_in_transaction(payer, &[instruction])
+    };
+
+    let mut batches = Vec::new();
+    let mut rest = recipients;
+    while !rest.is_empty() {
+        let mut len = 1;
+        while len < rest.len() && fits(&rest[..=len]) {
+            len += 1;
+        }
+        let (batch, remaining) = rest.split_at(len);
+        batches.push(batch);
+        rest = remaining;
+    }
+
+    batches
+}
+
+pub fn transfer_many(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    recipients: &[(Pubkey, u64)],
+) -> Result<()> {
+    for chunk in transfer_many_batches(&program.payer(), authority, mint, recipients) {
+        let instruction = transfer_many_instruction(authority, mint, chunk);
+
+        let signature = send_as_authority(program, authority, vec![instruction])
+            .context("Failed to send transfer_many transaction")?;
+
+        for (owner, amount) in chunk {
+            println!("✓ Transferred {} to {}", amount, owner);
+        }
+        println!("  Transaction: {}", signature);
+    }
+
+    let total: u64 = recipients.iter().map(|(_, amount)| amount).sum();
+    println!(
+        "✓ Transferred {} tokens to {} recipients",
+        total,
+        recipients.len()
+    );
+
+    Ok(())
+}
+
+pub fn set_mint_authority(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let set_authority = generated::set_mint_authority::SetMintAuthority { new_authority };
+    let accounts = generated::set_mint_authority::Accounts {
+        mint,
+        authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: set_authority.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send set_mint_authority transaction")?;
+
+    println!("✓ Mint authority updated");
+    println!("  Mint address: {}", mint);
+    println!("  New authority: {}", new_authority);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn revoke_mint_authority(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+) -> Result<()> {
+    let revoke = generated::revoke_mint_authority::RevokeMintAuthority {};
+    let accounts = generated::revoke_mint_authority::Accounts {
+        mint,
+        authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: revoke.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send revoke_mint_authority transaction")?;
+
+    println!("✓ Mint authority revoked, supply is now fixed");
+    println!("  Mint address: {}", mint);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn propose_mint_authority(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let propose = generated::propose_mint_authority::ProposeMintAuthority { new_authority };
+    let accounts = generated::propose_mint_authority::Accounts {
+        mint,
+        authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: propose.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send propose_mint_authority transaction")?;
+
+    println!("✓ Mint authority handover proposed");
+    println!("  Mint address: {}", mint);
+    println!("  Pending authority: {}", new_authority);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn accept_mint_authority(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+) -> Result<()> {
+    let accept = generated::accept_mint_authority::AcceptMintAuthority {};
+    let accounts = generated::accept_mint_authority::Accounts {
+        mint,
+        pending_authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: accept.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send accept_mint_authority transaction")?;
+
+    println!("✓ Mint authority handover accepted");
+    println!("  Mint address: {}", mint);
+    println!("  New authority: {}", authority.address);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn cancel_mint_authority_transfer(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+) -> Result<()> {
+    let cancel = generated::cancel_mint_authority_transfer::CancelMintAuthorityTransfer {};
+    let accounts = generated::cancel_mint_authority_transfer::Accounts {
+        mint,
+        authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: cancel.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send cancel_mint_authority_transfer transaction")?;
+
+    println!("✓ Mint authority handover cancelled");
+    println!("  Mint address: {}", mint);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn pause(program: &Program<Rc<Keypair>>, payer: &Keypair, mint: Pubkey) -> Result<()> {
+    let pause = generated::pause::Pause {};
+    let accounts = generated::pause::Accounts {
+        mint,
+        pause_authority: payer.pubkey(),
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: pause.data(),
+    };
+
+    let signature = program
+        .request()
+        .instruction(instruction)
+        .send()
+        .context("Failed to send pause transaction")?;
+
+    println!("✓ Mint paused");
+    println!("  Mint address: {}", mint);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn unpause(program: &Program<Rc<Keypair>>, payer: &Keypair, mint: Pubkey) -> Result<()> {
+    let unpause = generated::unpause::Unpause {};
+    let accounts = generated::unpause::Accounts {
+        mint,
+        pause_authority: payer.pubkey(),
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: unpause.data(),
+    };
+
+    let signature = program
+        .request()
+        .instruction(instruction)
+        .send()
+        .context("Failed to send unpause transaction")?;
+
+    println!("✓ Mint unpaused");
+    println!("  Mint address: {}", mint);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn freeze_account(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    owner: Pubkey,
+) -> Result<()> {
+    let freeze = generated::freeze_account::FreezeAccount {};
+    let accounts = generated::freeze_account::Accounts {
+        mint,
+        token_account: token_account_address(&owner, &mint),
+        freeze_authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: freeze.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send freeze_account transaction")?;
+
+    println!("✓ Token account frozen");
+    println!("  Owner: {}", owner);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn thaw_account(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    owner: Pubkey,
+) -> Result<()> {
+    let thaw = generated::thaw_account::ThawAccount {};
+    let accounts = generated::thaw_account::Accounts {
+        mint,
+        token_account: token_account_address(&owner, &mint),
+        freeze_authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: thaw.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send thaw_account transaction")?;
+
+    println!("✓ Token account thawed");
+    println!("  Owner: {}", owner);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+/// Yields the line number and trimmed comma-separated fields of each CSV row.
+/// Blank lines, `#` comments and an `owner` header row are skipped.
+fn csv_rows(contents: &str) -> impl Iterator<Item = (usize, Vec<&str>)> {
+    contents.lines().enumerate().filter_map(|(index, line)| {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let is_header = index == 0 && fields[0].eq_ignore_ascii_case("owner");
+        (!fields[0].is_empty() && !fields[0].starts_with('#') && !is_header)
+            .then_some((index + 1, fields))
+    })
+}
+
+/// Parses a list of owner addresses, one per line, taken from the first CSV column.
+pub fn parse_owner_list(contents: &str) -> Result<Vec<Pubkey>> {
+    csv_rows(contents)
+        .map(|(line, fields)| parse_pubkey(fields[0]).with_context(|| format!("Line {}", line)))
+        .collect()
+}
+
+/// Parses `owner,amount` CSV rows into mint recipients.
+pub fn parse_recipient_list(contents: &str) -> Result<Vec<(Pubkey, u64)>> {
+    csv_rows(contents)
+        .map(|(line, fields)| {
+            let owner = parse_pubkey(fields[0]).with_context(|| format!("Line {}", line))?;
+            let amount = fields
+                .get(1)
+                .with_context(|| format!("Line {}: missing amount", line))?
+                .parse()
+                .with_context(|| format!("Line {}: invalid amount", line))?;
+            Ok((owner, amount))
+        })
+        .collect()
+}
+
+const THAW_ACCOUNTS_PER_TX: usize = 10;
+
+pub fn thaw_accounts(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    owners: &[Pubkey],
+) -> Result<()> {
+    // The mint follows the owner after the 8-byte discriminator.
+    let filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        8 + 32,
+        mint.as_ref(),
+    ))];
+    let frozen_owners: Vec<Pubkey> = program
+        .accounts::<TokenAccount>(filters)
+        .context("Failed to fetch token accounts")?
+        .into_iter()
+        .map(|(_, account)| account)
+        .filter(|account| account.state == AccountState::Frozen)
+        .map(|account| account.owner)
+        .collect();
+
+    // Thawing an account that is missing or already thawed would fail the whole batch.
+    let (to_thaw, skipped): (Vec<Pubkey>, Vec<Pubkey>) = owners
+        .iter()
+        .partition(|owner| frozen_owners.contains(owner));
+    for owner in &skipped {
+        println!("  Skipping {}: no frozen token account", owner);
+    }
+
+    for chunk in to_thaw.chunks(THAW_ACCOUNTS_PER_TX) {
+        let instructions = chunk
+            .iter()
+            .map(|owner| {
+                let thaw = generated::thaw_account::ThawAccount {};
+                let accounts = generated::thaw_account::Accounts {
+                    mint,
+                    token_account: token_account_address(owner, &mint),
+                    freeze_authority: authority.address,
+                };
+                Instruction {
+                    program_id: ID,
+                    accounts: accounts.to_account_metas(None),
+                    data: thaw.data(),
+                }
+            })
+            .collect();
+
+        let signature = send_as_authority(program, authority, instructions)
+            .context("Failed to send thaw_account transaction")?;
+
+        for owner in chunk {
+            println!("✓ Thawed token account of {}", owner);
+        }
+        println!("  Transaction: {}", signature);
+    }
+
+    println!(
+        "✓ Thawed {} token accounts ({} skipped)",
+        to_thaw.len(),
+        skipped.len()
+    );
+
+    Ok(())
+}
+
+pub fn approve(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    delegate: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let approve = generated::approve::Approve { amount };
+    let accounts = generated::approve::Accounts {
+        token_account: token_account_address(&authority.address, &mint),
+        delegate,
+        owner: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: approve.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send approve transaction")?;
+
+    println!("✓ Delegate approved");
+    println!("  Delegate: {}", delegate);
+    println!("  Allowance: {}", amount);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn revoke(program: &Program<Rc<Keypair>>, authority: &Authority, mint: Pubkey) -> Result<()> {
+    let revoke = generated::revoke::Revoke {};
+    let accounts = generated::revoke::Accounts {
+        token_account: token_account_address(&authority.address, &mint),
+        owner: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: revoke.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send revoke transaction")?;
+
+    println!("✓ Delegate revoked");
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+/// A permit signed off-chain by a token account owner, which anyone can later submit
+/// to set the owner's delegate without the owner sending a transaction.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SignedPermit {
+    pub mint: String,
+    pub owner: String,
+    pub spender: String,
+    pub amount: u64,
+    pub nonce: u64,
+    /// Unix timestamp after which the permit can no longer be submitted
+    pub deadline: i64,
+    pub signature: String,
+}
+
+impl SignedPermit {
+    pub fn sign(
+        owner: &Keypair,
+        mint: Pubkey,
+        spender: Pubkey,
+        amount: u64,
+        nonce: u64,
+        deadline: i64,
+    ) -> Self {
+        let message = permit_message(&mint, &owner.pubkey(), &spender, amount, nonce, deadline);
+        SignedPermit {
+            mint: mint.to_string(),
+            owner: owner.pubkey().to_string(),
+            spender: spender.to_string(),
+            amount,
+            nonce,
+            deadline,
+            signature: owner.sign_message(&message).to_string(),
+        }
+    }
+
+    /// The Ed25519 signature check followed by the `permit` instruction that relies on it.
+    pub fn instructions(&self, payer: &Pubkey) -> Result<Vec<Instruction>> {
+        let mint = parse_pubkey(&self.mint)?;
+        let owner = parse_pubkey(&self.owner)?;
+        let spender = parse_pubkey(&self.spender)?;
+        let signature = Signature::from_str(&self.signature).context("Invalid permit signature")?;
+
+        let message = permit_message(
+            &mint,
+            &owner,
+            &spender,
+            self.amount,
+            self.nonce,
+            self.deadline,
+        );
+        anyhow::ensure!(
+            signature.verify(owner.as_ref(), &message),
+            "Permit signature does not match the owner and permit fields"
+        );
+
+        let permit = generated::permit::Permit {
+            spender,
+            amount: self.amount,
+            nonce: self.nonce,
+            deadline: self.deadline,
+        };
+        let accounts = generated::permit::Accounts {
+            token_account: token_account_address(&owner, &mint),
+            permit_nonce: permit_nonce_address(&owner, &mint, self.nonce),
+            instructions: sysvar::instructions::ID,
+            payer: *payer,
+            system_program: system_program::ID.to_bytes().into(),
+        };
+
+        Ok(vec![
+            new_ed25519_instruction_with_signature(
+                &message,
+                signature.as_array(),
+                &owner.to_bytes(),
+            ),
+            Instruction {
+                program_id: ID,
+                accounts: accounts.to_account_metas(None),
+                data: permit.data(),
+            },
+        ])
+    }
+}
+
+pub fn sign_permit(
+    owner: &Keypair,
+    mint: Pubkey,
+    spender: Pubkey,
+    amount: u64,
+    nonce: u64,
+    expires_in: i64,
+    output: &str,
+) -> Result<()> {
+    let permit = SignedPermit::sign(owner, mint, spender, amount, nonce, unix_now() + expires_in);
+
+    fs::write(output, serde_json::to_string_pretty(&permit)?)
+        .with_context(|| format!("Failed to write {}", output))?;
+
+    println!("✓ Permit signed");
+    println!("  Spender: {}", spender);
+    println!("  Allowance: {}", amount);
+    println!("  Nonce: {}", nonce);
+    println!("  Expires in: {} seconds", expires_in);
+    println!("  Written to: {}", output);
+
+    Ok(())
+}
+
+pub fn submit_permit(program: &Program<Rc<Keypair>>, payer: &Keypair, path: &str) -> Result<()> {
+    let contents = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+    let permit: SignedPermit =
+        serde_json::from_str(&contents).with_context(|| format!("Invalid permit in {}", path))?;
+
+    let mut request = program.request();
+    for instruction in permit.instructions(&payer.pubkey())? {
+        request = request.instruction(instruction);
+    }
+    let signature = request
+        .send()
+        .context("Failed to send permit transaction")?;
+
+    println!("✓ Permit submitted");
+    println!("  Owner: {}", permit.owner);
+    println!("  Delegate: {}", permit.spender);
+    println!("  Allowance: {}", permit.amount);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn cancel_permit(
+    program: &Program<Rc<Keypair>>,
+    payer: &Keypair,
+    authority: &Authority,
+    mint: Pubkey,
+    nonce: u64,
+) -> Result<()> {
+    let cancel_permit = generated::cancel_permit::CancelPermit { nonce };
+    let accounts = generated::cancel_permit::Accounts {
+        mint,
+        permit_nonce: permit_nonce_address(&authority.address, &mint, nonce),
+        owner: authority.address,
+        payer: payer.pubkey(),
+        system_program: system_program::ID.to_bytes().into(),
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: cancel_permit.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send cancel permit transaction")?;
+
+    println!("✓ Permit nonce cancelled");
+    println!("  Nonce: {}", nonce);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn update_metadata(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    name: Option<String>,
+    symbol: Option<String>,
+    uri: Option<String>,
+) -> Result<()> {
+    let update = generated::update_metadata::UpdateMetadata {
+        name: name.clone(),
+        symbol: symbol.clone(),
+        uri: uri.clone(),
+    };
+    let accounts = generated::update_metadata::Accounts {
+        metadata: metadata_address(&mint),
+        update_authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: update.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send update_metadata transaction")?;
+
+    println!("✓ Metadata updated");
+    println!("  Mint address: {}", mint);
+    if let Some(name) = name {
+        println!("  Name: {}", name);
+    }
+    if let Some(symbol) = symbol {
+        println!("  Symbol: {}", symbol);
+    }
+    if let Some(uri) = uri {
+        println!("  URI: {}", uri);
+    }
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn set_metadata_field(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    key: String,
+    value: String,
+) -> Result<()> {
+    let set_field = generated::set_metadata_field::SetMetadataField {
+        key: key.clone(),
+        value: value.clone(),
+    };
+    let accounts = generated::set_metadata_field::Accounts {
+        metadata: metadata_address(&mint),
+        update_authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: set_field.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send set_metadata_field transaction")?;
+
+    println!("✓ Metadata field set");
+    println!("  Mint address: {}", mint);
+    println!("  {}: {}", key, value);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn remove_metadata_field(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    key: String,
+) -> Result<()> {
+    let remove_field = generated::remove_metadata_field::RemoveMetadataField { key: key.clone() };
+    let accounts = generated::remove_metadata_field::Accounts {
+        metadata: metadata_address(&mint),
+        update_authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: remove_field.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send remove_metadata_field transaction")?;
+
+    println!("✓ Metadata field removed");
+    println!("  Mint address: {}", mint);
+    println!("  Key: {}", key);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn set_metadata_update_authority(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    new_authority: Option<Pubkey>,
+) -> Result<()> {
+    let set_authority =
+        generated::set_metadata_update_authority::SetMetadataUpdateAuthority { new_authority };
+    let accounts = generated::set_metadata_update_authority::Accounts {
+        metadata: metadata_address(&mint),
+        update_authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: set_authority.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send set_metadata_update_authority transaction")?;
+
+    println!("✓ Metadata update authority changed");
+    println!("  Mint address: {}", mint);
+    println!("  New authority: {}", format_optional(new_authority));
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn set_transfer_fee(
+    program: &Program<Rc<Keypair>>,
+    payer: &Keypair,
+    mint: Pubkey,
+    basis_points: u16,
+    maximum_fee: u64,
+) -> Result<()> {
+    let set_fee = generated::set_transfer_fee::SetTransferFee {
+        basis_points,
+        maximum_fee,
+    };
+    let accounts = generated::set_transfer_fee::Accounts {
+        mint,
+        fee_authority: payer.pubkey(),
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: set_fee.data(),
+    };
+
+    let signature = program
+        .request()
+        .instruction(instruction)
+        .send()
+        .context("Failed to send set_transfer_fee transaction")?;
+
+    println!("✓ Transfer fee updated");
+    println!("  Mint address: {}", mint);
+    println!("  Basis points: {}", basis_points);
+    println!("  Maximum fee: {}", maximum_fee);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+const HARVEST_ACCOUNTS_PER_TX: usize = 10;
+
+pub fn harvest_fees(program: &Program<Rc<Keypair>>, payer: &Keypair, mint: Pubkey) -> Result<()> {
+    // The mint follows the owner after the 8-byte discriminator.
+    let filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        8 + 32,
+        mint.as_ref(),
+    ))];
+    let accounts: Vec<TokenAccount> = program
+        .accounts::<TokenAccount>(filters)
+        .context("Failed to fetch token accounts")?
+        .into_iter()
+        .map(|(_, account)| account)
+        .filter(|account| account.withheld_amount > 0)
+        .collect();
+
+    if accounts.is_empty() {
+        println!("No withheld fees found for mint {}", mint);
+        return Ok(());
+    }
+
+    for chunk in accounts.chunks(HARVEST_ACCOUNTS_PER_TX) {
+        let mut request = program.request();
+        for account in chunk {
+            let harvest = generated::harvest_withheld::HarvestWithheld {};
+            let harvest_accounts = generated::harvest_withheld::Accounts {
+                mint,
+                token_account: token_account_address(&account.owner, &mint),
+                fee_authority: payer.pubkey(),
+            };
+            request = request.instruction(Instruction {
+                program_id: ID,
+                accounts: harvest_accounts.to_account_metas(None),
+                data: harvest.data(),
+            });
+        }
+
+        let signature = request
+            .send()
+            .context("Failed to send harvest_withheld transaction")?;
+
+        for account in chunk {
+            println!(
+                "✓ Harvested {} from {}",
+                account.withheld_amount, account.owner
+            );
+        }
+        println!("  Transaction: {}", signature);
+    }
+
+    let total: u64 = accounts.iter().map(|account| account.withheld_amount).sum();
+    println!(
+        "✓ Harvested {} withheld tokens from {} accounts",
+        total,
+        accounts.len()
+    );
+
+    Ok(())
+}
+
+pub fn withdraw_fees(
+    program: &Program<Rc<Keypair>>,
+    payer: &Keypair,
+    mint: Pubkey,
+    owner: Pubkey,
+) -> Result<()> {
+    let withdraw = generated::withdraw_withheld::WithdrawWithheld {};
+    let accounts = generated::withdraw_withheld::Accounts {
+        mint,
+        destination: token_account_address(&owner, &mint),
+        fee_authority: payer.pubkey(),
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: withdraw.data(),
+    };
+
+    let signature = program
+        .request()
+        .instruction(instruction)
+        .send()
+        .context("Failed to send withdraw_withheld transaction")?;
+
+    println!("✓ Withheld fees withdrawn");
+    println!("  Mint address: {}", mint);
+    println!("  Destination owner: {}", owner);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+/// Moves `amount` out of `owner`'s token account as the mint's permanent delegate,
+/// transferring it to `destination`'s token account or burning it if `None`.
+pub fn clawback(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    owner: Pubkey,
+    destination: Option<Pubkey>,
+    amount: u64,
+) -> Result<()> {
+    let source = token_account_address(&owner, &mint);
+    let instruction = match destination {
+        Some(destination) => {
+            let transfer = generated::transfer::Transfer { amount };
+            let accounts = generated::transfer::Accounts {
+                mint,
+                from: source,
+                to: token_account_address(&destination, &mint),
+                authority: authority.address,
+                from_deny_entry: denylist_address(&mint, &owner),
+                to_deny_entry: denylist_address(&mint, &destination),
+            };
+            Instruction {
+                program_id: ID,
+                accounts: accounts.to_account_metas(None),
+                data: transfer.data(),
+            }
+        }
+        None => {
+            let burn = generated::burn::Burn { amount };
+            let accounts = generated::burn::Accounts {
+                mint,
+                token_account: source,
+                authority: authority.address,
+                deny_entry: denylist_address(&mint, &owner),
+            };
+            Instruction {
+                program_id: ID,
+                accounts: accounts.to_account_metas(None),
+                data: burn.data(),
+            }
+        }
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send clawback transaction")?;
+
+    println!("✓ Tokens clawed back");
+    println!("  Mint address: {}", mint);
+    println!("  From owner: {}", owner);
+    match destination {
+        Some(destination) => println!("  Transferred {} to {}", amount, destination),
+        None => println!("  Burned {}", amount),
+    }
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn add_to_denylist(
+    program: &Program<Rc<Keypair>>,
+    payer: &Keypair,
+    authority: &Authority,
+    mint: Pubkey,
+    wallet: Pubkey,
+) -> Result<()> {
+    let add = generated::add_to_denylist::AddToDenylist {};
+    let accounts = generated::add_to_denylist::Accounts {
+        mint,
+        deny_entry: denylist_address(&mint, &wallet),
+        wallet,
+        freeze_authority: authority.address,
+        payer: payer.pubkey(),
+        system_program: system_program::ID.to_bytes().into(),
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: add.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send add_to_denylist transaction")?;
+
+    println!("✓ Wallet added to denylist");
+    println!("  Mint address: {}", mint);
+    println!("  Wallet: {}", wallet);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn remove_from_denylist(
+    program: &Program<Rc<Keypair>>,
+    payer: &Keypair,
+    authority: &Authority,
+    mint: Pubkey,
+    wallet: Pubkey,
+) -> Result<()> {
+    let remove = generated::remove_from_denylist::RemoveFromDenylist {};
+    let accounts = generated::remove_from_denylist::Accounts {
+        mint,
+        deny_entry: denylist_address(&mint, &wallet),
+        destination: payer.pubkey(),
+        freeze_authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: remove.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send remove_from_denylist transaction")?;
+
+    println!("✓ Wallet removed from denylist");
+    println!("  Mint address: {}", mint);
+    println!("  Wallet: {}", wallet);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn list_denylist(program: &Program<Rc<Keypair>>, mint: Pubkey) -> Result<()> {
+    // The mint is the first field after the 8-byte discriminator.
+    let filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        8,
+        mint.as_ref(),
+    ))];
+    let entries = program
+        .accounts::<DenylistEntry>(filters)
+        .context("Failed to fetch denylist entries")?;
+
+    println!("Denylist for mint {}: {} entries", mint, entries.len());
+    for (_, entry) in entries {
+        println!("  {}", entry.wallet);
+    }
+
+    Ok(())
+}
+
+pub fn create_multisig(
+    program: &Program<Rc<Keypair>>,
+    payer: &Keypair,
+    threshold: u8,
+    signers: Vec<Pubkey>,
+) -> Result<()> {
+    let multisig = generate_keypair_file("multisig")?;
+
+    let create = generated::create_multisig::CreateMultisig {
+        threshold,
+        signers: signers.clone(),
+    };
+    let accounts = generated::create_multisig::Accounts {
+        multisig: multisig.pubkey(),
+        payer: payer.pubkey(),
+        system_program: system_program::ID.to_bytes().into(),
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: create.data(),
+    };
+
+    let signature = program
+        .request()
+        .instruction(instruction)
+        .signer(&multisig)
+        .send()
+        .context("Failed to send create_multisig transaction")?;
+
+    println!("✓ Multisig created");
+    println!("  Multisig address: {}", multisig.pubkey());
+    println!("  Threshold: {} of {}", threshold, signers.len());
+    for signer in signers {
+        println!("  Signer: {}", signer);
+    }
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn add_minter(
+    program: &Program<Rc<Keypair>>,
+    payer: &Keypair,
+    authority: &Authority,
+    mint: Pubkey,
+    minter: Pubkey,
+    allowance: u64,
+) -> Result<()> {
+    let add = generated::add_minter::AddMinter { allowance };
+    let accounts = generated::add_minter::Accounts {
+        mint,
+        minter_role: minter_role_address(&mint, &minter),
+        minter,
+        authority: authority.address,
+        payer: payer.pubkey(),
+        system_program: system_program::ID.to_bytes().into(),
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: add.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send add_minter transaction")?;
+
+    println!("✓ Minter added");
+    println!("  Mint address: {}", mint);
+    println!("  Minter: {}", minter);
+    println!("  Allowance: {}", allowance);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn set_minter_allowance(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    minter: Pubkey,
+    allowance: u64,
+) -> Result<()> {
+    let set_allowance = generated::set_minter_allowance::SetMinterAllowance { allowance };
+    let accounts = generated::set_minter_allowance::Accounts {
+        mint,
+        minter_role: minter_role_address(&mint, &minter),
+        authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: set_allowance.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send set_minter_allowance transaction")?;
+
+    println!("✓ Minter allowance updated");
+    println!("  Mint address: {}", mint);
+    println!("  Minter: {}", minter);
+    println!("  Allowance: {}", allowance);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn remove_minter(
+    program: &Program<Rc<Keypair>>,
+    payer: &Keypair,
+    authority: &Authority,
+    mint: Pubkey,
+    minter: Pubkey,
+) -> Result<()> {
+    let remove = generated::remove_minter::RemoveMinter {};
+    let accounts = generated::remove_minter::Accounts {
+        mint,
+        minter_role: minter_role_address(&mint, &minter),
+        destination: payer.pubkey(),
+        authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: remove.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send remove_minter transaction")?;
+
+    println!("✓ Minter removed");
+    println!("  Mint address: {}", mint);
+    println!("  Minter: {}", minter);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn set_mint_delay(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    delay: i64,
+) -> Result<()> {
+    let mint_account: TokenMint = program
+        .account(mint)
+        .context("Failed to fetch mint account")?;
+
+    let set_delay = generated::set_mint_delay::SetMintDelay { delay };
+    let accounts = generated::set_mint_delay::Accounts {
+        mint,
+        authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: set_delay.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send set_mint_delay transaction")?;
+
+    if delay < mint_account.mint_delay {
+        println!("✓ Mint delay change queued");
+        println!("  Mint address: {}", mint);
+        println!("  Delay: {} seconds", delay);
+        println!(
+            "  Applicable in {} seconds with apply-mint-delay",
+            mint_account.mint_delay
+        );
+    } else {
+        println!("✓ Mint delay updated");
+        println!("  Mint address: {}", mint);
+        println!("  Delay: {} seconds", delay);
+    }
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn apply_mint_delay(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+) -> Result<()> {
+    let apply_delay = generated::apply_mint_delay::ApplyMintDelay {};
+    let accounts = generated::apply_mint_delay::Accounts {
+        mint,
+        authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: apply_delay.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send apply_mint_delay transaction")?;
+
+    println!("✓ Mint delay applied");
+    println!("  Mint address: {}", mint);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn set_mint_rate_limit(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    limit: u64,
+    window: i64,
+) -> Result<()> {
+    let set_rate_limit = generated::set_mint_rate_limit::SetMintRateLimit { limit, window };
+    let accounts = generated::set_mint_rate_limit::Accounts {
+        mint,
+        authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: set_rate_limit.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send set_mint_rate_limit transaction")?;
+
+    println!("✓ Mint rate limit updated");
+    println!("  Mint address: {}", mint);
+    println!("  Limit: {} per {} seconds", limit, window);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn queue_mint(
+    program: &Program<Rc<Keypair>>,
+    payer: &Keypair,
+    authority: &Authority,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let mint_account: TokenMint = program
+        .account(mint)
+        .context("Failed to fetch mint account")?;
+    let id = mint_account.queued_mint_count;
+
+    let queue = generated::queue_mint::QueueMint { amount };
+    let accounts = generated::queue_mint::Accounts {
+        mint,
+        token_account: token_account_address(&owner, &mint),
+        pending_mint: pending_mint_address(&mint, id),
+        authority: authority.address,
+        payer: payer.pubkey(),
+        system_program: system_program::ID.to_bytes().into(),
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: queue.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send queue_mint transaction")?;
+
+    println!("✓ Mint queued");
+    println!("  Mint address: {}", mint);
+    println!("  Queued mint id: {}", id);
+    println!("  Amount: {} to {}", amount, owner);
+    println!(
+        "  Executable in {} seconds with execute-mint",
+        mint_account.mint_delay
+    );
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn execute_mint(
+    program: &Program<Rc<Keypair>>,
+    payer: &Keypair,
+    authority: &Authority,
+    mint: Pubkey,
+    id: u64,
+) -> Result<()> {
+    let pending_mint_key = pending_mint_address(&mint, id);
+    let pending_mint: PendingMint = program
+        .account(pending_mint_key)
+        .context("Failed to fetch queued mint")?;
+    let token_account: TokenAccount = program
+        .account(pending_mint.token_account)
+        .context("Failed to fetch token account")?;
+
+    let execute = generated::execute_mint::ExecuteMint {};
+    let accounts = generated::execute_mint::Accounts {
+        mint,
+        pending_mint: pending_mint_key,
+        token_account: pending_mint.token_account,
+        deny_entry: denylist_address(&mint, &token_account.owner),
+        destination: payer.pubkey(),
+        authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: execute.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send execute_mint transaction")?;
+
+    println!("✓ Queued mint executed");
+    println!("  Mint address: {}", mint);
+    println!(
+        "  Minted {} to {}",
+        pending_mint.amount, token_account.owner
+    );
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn cancel_mint(
+    program: &Program<Rc<Keypair>>,
+    payer: &Keypair,
+    authority: &Authority,
+    mint: Pubkey,
+    id: u64,
+) -> Result<()> {
+    let cancel = generated::cancel_mint::CancelMint {};
+    let accounts = generated::cancel_mint::Accounts {
+        mint,
+        pending_mint: pending_mint_address(&mint, id),
+        destination: payer.pubkey(),
+        authority: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: cancel.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send cancel_mint transaction")?;
+
+    println!("✓ Queued mint cancelled");
+    println!("  Mint address: {}", mint);
+    println!("  Queued mint id: {}", id);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+/// When a new vesting schedule unlocks its tokens.
+#[derive(Debug, Clone, Copy)]
+pub struct VestingSchedule {
+    /// Unix timestamp vesting starts from (now if not provided)
+    pub start_time: Option<i64>,
+    /// Seconds after the start before any tokens can be claimed
+    pub cliff_seconds: i64,
+    /// Seconds after the start until all tokens have vested
+    pub duration_seconds: i64,
+    /// Lets the grantor take back the unvested tokens
+    pub revocable: bool,
+}
+
+pub fn create_vesting(
+    program: &Program<Rc<Keypair>>,
+    payer: &Keypair,
+    authority: &Authority,
+    mint: Pubkey,
+    beneficiary: Pubkey,
+    amount: u64,
+    schedule: VestingSchedule,
+) -> Result<()> {
+    let mint_account: TokenMint = program
+        .account(mint)
+        .context("Failed to fetch mint account")?;
+    let id = mint_account.vesting_count;
+
+    let start_time = schedule.start_time.unwrap_or_else(unix_now);
+    let cliff_time = start_time.saturating_add(schedule.cliff_seconds);
+    let end_time = start_time.saturating_add(schedule.duration_seconds);
+
+    let create = generated::create_vesting::CreateVesting {
+        amount,
+        start_time,
+        cliff_time,
+        end_time,
+        revocable: schedule.revocable,
+    };
+    let accounts = generated::create_vesting::Accounts {
+        mint,
+        source: token_account_address(&authority.address, &mint),
+        vesting: vesting_address(&mint, id),
+        beneficiary,
+        deny_entry: denylist_address(&mint, &authority.address),
+        grantor: authority.address,
+        payer: payer.pubkey(),
+        system_program: system_program::ID.to_bytes().into(),
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: create.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send create_vesting transaction")?;
+
+    println!("✓ Vesting schedule created");
+    println!("  Vesting id: {}", id);
+    println!("  Beneficiary: {}", beneficiary);
+    println!("  Amount: {}", amount);
+    println!(
+        "  Vests from unix time {} to {} (cliff at {})",
+        start_time, end_time, cliff_time
+    );
+    println!("  Revocable: {}", schedule.revocable);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn claim_vested(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    id: u64,
+) -> Result<()> {
+    let vesting: VestingAccount = program
+        .account(vesting_address(&mint, id))
+        .context("Failed to fetch vesting account")?;
+
+    let claim = generated::claim_vested::ClaimVested {};
+    let accounts = generated::claim_vested::Accounts {
+        mint,
+        vesting: vesting_address(&mint, id),
+        token_account: token_account_address(&authority.address, &mint),
+        deny_entry: denylist_address(&mint, &authority.address),
+        beneficiary: authority.address,
+        grantor: vesting.grantor,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: claim.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send claim_vested transaction")?;
+
+    println!("✓ Vested tokens claimed");
+    // A fully claimed schedule is closed by the claim.
+    match program.account::<VestingAccount>(vesting_address(&mint, id)) {
+        Ok(vesting) => println!(
+            "  Claimed so far: {} of {}",
+            vesting.claimed_amount, vesting.total_amount
+        ),
+        Err(_) => println!("  Fully claimed; the vesting account was closed"),
+    }
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn revoke_vesting(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    id: u64,
+) -> Result<()> {
+    let revoke = generated::revoke_vesting::RevokeVesting {};
+    let accounts = generated::revoke_vesting::Accounts {
+        mint,
+        vesting: vesting_address(&mint, id),
+        token_account: token_account_address(&authority.address, &mint),
+        deny_entry: denylist_address(&mint, &authority.address),
+        grantor: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: revoke.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send revoke_vesting transaction")?;
+
+    println!("✓ Vesting revoked");
+    // With nothing left to claim the schedule is closed by the revoke.
+    match program.account::<VestingAccount>(vesting_address(&mint, id)) {
+        Ok(vesting) => println!(
+            "  Left to the beneficiary: {} ({} unclaimed)",
+            vesting.total_amount,
+            vesting.total_amount - vesting.claimed_amount
+        ),
+        Err(_) => println!("  Nothing left to claim; the vesting account was closed"),
+    }
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn list_vesting(
+    program: &Program<Rc<Keypair>>,
+    mint: Pubkey,
+    beneficiary: Option<Pubkey>,
+) -> Result<()> {
+    // The mint is the first field after the 8-byte discriminator, followed by the
+    // id and the grantor.
+    let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        8,
+        mint.as_ref(),
+    ))];
+    if let Some(beneficiary) = beneficiary {
+        filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            8 + 32 + 8 + 32,
+            beneficiary.as_ref(),
+        )));
+    }
+    let mut schedules = program
+        .accounts::<VestingAccount>(filters)
+        .context("Failed to fetch vesting schedules")?;
+    schedules.sort_by_key(|(_, vesting)| vesting.id);
+
+    let now = unix_now();
+    println!("Vesting schedules for mint {}: {}", mint, schedules.len());
+    for (_, vesting) in schedules {
+        let vested = vesting.vested_amount(now);
+        println!("  Vesting #{}", vesting.id);
+        println!("    Grantor: {}", vesting.grantor);
+        println!("    Beneficiary: {}", vesting.beneficiary);
+        println!(
+            "    Vested: {} of {} ({} claimed, {} claimable now)",
+            vested,
+            vesting.total_amount,
+            vesting.claimed_amount,
+            vested.saturating_sub(vesting.claimed_amount)
+        );
+        println!(
+            "    Schedule: unix time {} to {}, cliff at {}",
+            vesting.start_time, vesting.end_time, vesting.cliff_time
+        );
+        println!("    Revocable: {}", vesting.revocable);
+    }
+
+    Ok(())
+}
+
+pub fn make_offer(
+    program: &Program<Rc<Keypair>>,
+    payer: &Keypair,
+    authority: &Authority,
+    offer_mint: Pubkey,
+    offer_amount: u64,
+    request_mint: Pubkey,
+    request_amount: u64,
+) -> Result<()> {
+    // Any id unused by the maker will do; the clock keeps them unique in practice.
+    let id = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+    let offer = offer_address(&authority.address, id);
+
+    let make = generated::make_offer::MakeOffer {
+        id,
+        offer_amount,
+        request_amount,
+    };
+    let accounts = generated::make_offer::Accounts {
+        offer_mint,
+        request_mint,
+        source: token_account_address(&authority.address, &offer_mint),
+        offer,
+        escrow: token_account_address(&offer, &offer_mint),
+        deny_entry: denylist_address(&offer_mint, &authority.address),
+        maker: authority.address,
+        payer: payer.pubkey(),
+        system_program: system_program::ID.to_bytes().into(),
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: make.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send make_offer transaction")?;
+
+    println!("✓ Offer made");
+    println!("  Offer address: {}", offer);
+    println!("  Offering: {} of {}", offer_amount, offer_mint);
+    println!("  Requesting: {} of {}", request_amount, request_mint);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn take_offer(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    offer: Pubkey,
+) -> Result<()> {
+    let offer_account: Offer = program.account(offer).context("Failed to fetch offer")?;
+    let maker = offer_account.maker;
+    let taker = authority.address;
+
+    let take = generated::take_offer::TakeOffer {};
+    let accounts = generated::take_offer::Accounts {
+        offer,
+        offer_mint: offer_account.offer_mint,
+        request_mint: offer_account.request_mint,
+        escrow: token_account_address(&offer, &offer_account.offer_mint),
+        maker_offer_account: token_account_address(&maker, &offer_account.offer_mint),
+        maker_request_account: token_account_address(&maker, &offer_account.request_mint),
+        taker_offer_account: token_account_address(&taker, &offer_account.offer_mint),
+        taker_request_account: token_account_address(&taker, &offer_account.request_mint),
+        maker_offer_deny_entry: denylist_address(&offer_account.offer_mint, &maker),
+        maker_request_deny_entry: denylist_address(&offer_account.request_mint, &maker),
+        taker_offer_deny_entry: denylist_address(&offer_account.offer_mint, &taker),
+        taker_request_deny_entry: denylist_address(&offer_account.request_mint, &taker),
+        maker,
+        taker,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: take.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send take_offer transaction")?;
+
+    println!("✓ Offer taken");
+    println!(
+        "  Received: {} of {}",
+        offer_account.offer_amount, offer_account.offer_mint
+    );
+    println!(
+        "  Paid: {} of {}",
+        offer_account.request_amount, offer_account.request_mint
+    );
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn cancel_offer(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    offer: Pubkey,
+) -> Result<()> {
+    let offer_account: Offer = program.account(offer).context("Failed to fetch offer")?;
+
+    let cancel = generated::cancel_offer::CancelOffer {};
+    let accounts = generated::cancel_offer::Accounts {
+        offer,
+        offer_mint: offer_account.offer_mint,
+        escrow: token_account_address(&offer, &offer_account.offer_mint),
+        token_account: token_account_address(&authority.address, &offer_account.offer_mint),
+        deny_entry: denylist_address(&offer_account.offer_mint, &authority.address),
+        maker: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: cancel.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send cancel_offer transaction")?;
+
+    println!("✓ Offer cancelled");
+    println!(
+        "  Returned: {} of {}",
+        offer_account.offer_amount, offer_account.offer_mint
+    );
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn list_offers(program: &Program<Rc<Keypair>>, mint: Option<Pubkey>) -> Result<()> {
+    let mut offers = match mint {
+        // The offered mint follows the maker and id after the 8-byte discriminator,
+        // and the requested mint follows the offered amount.
+        Some(mint) => {
+            let mut offers = Vec::new();
+            for offset in [8 + 32 + 8, 8 + 32 + 8 + 32 + 8] {
+                let filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    offset,
+                    mint.as_ref(),
+                ))];
+                offers.extend(
+                    program
+                        .accounts::<Offer>(filters)
+                        .context("Failed to fetch offers")?,
+                );
+            }
+            offers
+        }
+        None => program
+            .accounts::<Offer>(Vec::new())
+            .context("Failed to fetch offers")?,
+    };
+    offers.sort_by_key(|(_, offer)| (offer.maker, offer.id));
+
+    println!("Open offers: {}", offers.len());
+    for (address, offer) in offers {
+        println!("  Offer {}", address);
+        println!("    Maker: {}", offer.maker);
+        println!(
+            "    Offering: {} of {}",
+            offer.offer_amount, offer.offer_mint
+        );
+        println!(
+            "    Requesting: {} of {}",
+            offer.request_amount, offer.request_mint
+        );
+    }
+
+    Ok(())
+}
+
+/// Builds the merkle tree over `recipients`, each leaf indexed by its position, and
+/// returns the root together with the proof of every leaf.
+pub fn build_merkle_tree(recipients: &[(Pubkey, u64)]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+    let mut level: Vec<[u8; 32]> = recipients
+        .iter()
+        .enumerate()
+        .map(|(index, (claimant, amount))| airdrop_leaf(index as u64, claimant, *amount))
+        .collect();
+    let mut proofs = vec![Vec::new(); recipients.len()];
+    // Position of each leaf's ancestor in the current level.
+    let mut positions: Vec<usize> = (0..recipients.len()).collect();
+
+    while level.len() > 1 {
+        for (proof, position) in proofs.iter_mut().zip(positions.iter_mut()) {
+            // The last node of an odd level has no sibling and moves up unchanged.
+            if let Some(sibling) = level.get(*position ^ 1) {
+                proof.push(*sibling);
+            }
+            *position /= 2;
+        }
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => merkle_parent(left, right),
+                [single] => *single,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+
+    (level.first().copied().unwrap_or_default(), proofs)
+}
+
+/// One recipient's entry in an airdrop file, with its merkle proof.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct AirdropClaim {
+    pub index: u64,
+    pub claimant: String,
+    pub amount: u64,
+    pub proof: Vec<String>,
+}
+
+/// An airdrop file written by `airdrop-build`; hashes are base58 encoded.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Airdrop {
+    pub mint: String,
+    pub merkle_root: String,
+    pub total_amount: u64,
+    pub claims: Vec<AirdropClaim>,
+}
+
+impl Airdrop {
+    pub fn new(mint: Pubkey, recipients: &[(Pubkey, u64)]) -> Self {
+        let (root, proofs) = build_merkle_tree(recipients);
+        Airdrop {
+            mint: mint.to_string(),
+            merkle_root: Hash::new_from_array(root).to_string(),
+            total_amount: recipients.iter().map(|(_, amount)| amount).sum(),
+            claims: recipients
+                .iter()
+                .zip(proofs)
+                .enumerate()
+                .map(|(index, ((claimant, amount), proof))| AirdropClaim {
+                    index: index as u64,
+                    claimant: claimant.to_string(),
+                    amount: *amount,
+                    proof: proof
+                        .into_iter()
+                        .map(|node| Hash::new_from_array(node).to_string())
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn load(path: &str) -> Result<Self> {
+        let contents =
+            fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+        serde_json::from_str(&contents).with_context(|| format!("Invalid airdrop in {}", path))
+    }
+
+    fn distributor(&self) -> Result<(Pubkey, Pubkey, [u8; 32])> {
+        let mint = parse_pubkey(&self.mint)?;
+        let root = parse_hash(&self.merkle_root)?;
+        Ok((mint, distributor_address(&mint, &root), root))
+    }
+}
+
+fn parse_hash(value: &str) -> Result<[u8; 32]> {
+    Ok(Hash::from_str(value)
+        .map_err(|_| anyhow::anyhow!("Invalid hash: {}", value))?
+        .to_bytes())
+}
+
+pub fn airdrop_build(mint: Pubkey, recipients: &[(Pubkey, u64)], output: &str) -> Result<()> {
+    anyhow::ensure!(!recipients.is_empty(), "No recipients to airdrop to");
+
+    let airdrop = Airdrop::new(mint, recipients);
+    fs::write(output, serde_json::to_string_pretty(&airdrop)?)
+        .with_context(|| format!("Failed to write {}", output))?;
+
+    println!("✓ Airdrop built");
+    println!("  Merkle root: {}", airdrop.merkle_root);
+    println!("  Recipients: {}", airdrop.claims.len());
+    println!("  Total amount: {}", airdrop.total_amount);
+    println!("  Written to: {}", output);
+
+    Ok(())
+}
+
+const CLAIM_BITMAPS_PER_TX: usize = 4;
+
+pub fn airdrop_create(
+    program: &Program<Rc<Keypair>>,
+    payer: &Keypair,
+    authority: &Authority,
+    path: &str,
+    duration_seconds: i64,
+) -> Result<()> {
+    let airdrop = Airdrop::load(path)?;
+    let (mint, distributor, merkle_root) = airdrop.distributor()?;
+    let num_claims = airdrop.claims.len() as u64;
+    let end_time = unix_now() + duration_seconds;
+
+    let create = generated::create_distributor::CreateDistributor {
+        merkle_root,
+        num_claims,
+        total_amount: airdrop.total_amount,
+        end_time,
+    };
+    let accounts = generated::create_distributor::Accounts {
+        mint,
+        source: token_account_address(&authority.address, &mint),
+        distributor,
+        vault: token_account_address(&distributor, &mint),
+        deny_entry: denylist_address(&mint, &authority.address),
+        funder: authority.address,
+        payer: payer.pubkey(),
+        system_program: system_program::ID.to_bytes().into(),
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: create.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send create_distributor transaction")?;
+
+    println!("✓ Distributor created");
+    println!("  Distributor address: {}", distributor);
+    println!("  Funded with: {}", airdrop.total_amount);
+    println!("  Claimable for: {} seconds", duration_seconds);
+    println!("  Transaction: {}", signature);
+
+    // Claims are recorded in bitmaps that must exist before anything can be claimed.
+    let chunks: Vec<u64> = (0..num_claims.div_ceil(CLAIMS_PER_BITMAP)).collect();
+    for batch in chunks.chunks(CLAIM_BITMAPS_PER_TX) {
+        let mut request = program.request();
+        for &chunk in batch {
+            let create = generated::create_claim_bitmap::CreateClaimBitmap { chunk };
+            let accounts = generated::create_claim_bitmap::Accounts {
+                distributor,
+                claim_bitmap: claim_bitmap_address(&distributor, chunk),
+                payer: payer.pubkey(),
+                system_program: system_program::ID.to_bytes().into(),
+            };
+            request = request.instruction(Instruction {
+                program_id: ID,
+                accounts: accounts.to_account_metas(None),
+                data: create.data(),
+            });
+        }
+
+        let signature = request
+            .send()
+            .context("Failed to send create_claim_bitmap transaction")?;
+
+        println!("✓ Created {} claim bitmaps", batch.len());
+        println!("  Transaction: {}", signature);
+    }
+
+    Ok(())
+}
+
+fn fetch_claim_bitmaps(
+    program: &Program<Rc<Keypair>>,
+    distributor: &Pubkey,
+) -> Result<Vec<ClaimBitmap>> {
+    // The distributor follows the 8-byte discriminator of its claim bitmaps.
+    let filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        8,
+        distributor.as_ref(),
+    ))];
+    Ok(program
+        .accounts::<ClaimBitmap>(filters)
+        .context("Failed to fetch claim bitmaps")?
+        .into_iter()
+        .map(|(_, bitmap)| bitmap)
+        .collect())
+}
+
+pub fn airdrop_claim(
+    program: &Program<Rc<Keypair>>,
+    path: &str,
+    claimant: Option<Pubkey>,
+) -> Result<()> {
+    let airdrop = Airdrop::load(path)?;
+    let (mint, distributor, _) = airdrop.distributor()?;
+
+    let bitmaps = fetch_claim_bitmaps(program, &distributor)?;
+    let is_claimed = |index: u64| {
+        let bit = (index % CLAIMS_PER_BITMAP) as usize;
+        bitmaps
+            .iter()
+            .find(|bitmap| bitmap.chunk == index / CLAIMS_PER_BITMAP)
+            .is_some_and(|bitmap| bitmap.bits[bit / 8] & (1 << (bit % 8)) != 0)
+    };
+
+    let mut claimed = 0;
+    let mut skipped = 0;
+    for entry in &airdrop.claims {
+        let owner = parse_pubkey(&entry.claimant)?;
+        if claimant.is_some_and(|claimant| claimant != owner) {
+            continue;
+        }
+        if is_claimed(entry.index) {
+            println!("  Skipping #{} for {}: already claimed", entry.index, owner);
+            skipped += 1;
+            continue;
+        }
+
+        let claim = generated::claim::Claim {
+            index: entry.index,
+            amount: entry.amount,
+            proof: entry
+                .proof
+                .iter()
+                .map(|node| parse_hash(node))
+                .collect::<Result<_>>()?,
+        };
+        let accounts = generated::claim::Accounts {
+            mint,
+            distributor,
+            vault: token_account_address(&distributor, &mint),
+            claim_bitmap: claim_bitmap_address(&distributor, entry.index / CLAIMS_PER_BITMAP),
+            token_account: token_account_address(&owner, &mint),
+            deny_entry: denylist_address(&mint, &owner),
+        };
+
+        let signature = program
+            .request()
+            .instruction(Instruction {
+                program_id: ID,
+                accounts: accounts.to_account_metas(None),
+                data: claim.data(),
+            })
+            .send()
+            .with_context(|| format!("Failed to send claim #{} transaction", entry.index))?;
+
+        println!("✓ Claimed {} for {}", entry.amount, owner);
+        println!("  Transaction: {}", signature);
+        claimed += 1;
+    }
+
+    println!("✓ Submitted {} claims ({} skipped)", claimed, skipped);
+
+    Ok(())
+}
+
+pub fn airdrop_close(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    path: &str,
+) -> Result<()> {
+    let airdrop = Airdrop::load(path)?;
+    let (mint, distributor, _) = airdrop.distributor()?;
+
+    // The program only closes the distributor once all of its bitmaps are closed.
+    let chunks: Vec<u64> = fetch_claim_bitmaps(program, &distributor)?
+        .iter()
+        .map(|bitmap| bitmap.chunk)
+        .collect();
+    for batch in chunks.chunks(CLAIM_BITMAPS_PER_TX) {
+        let instructions = batch
+            .iter()
+            .map(|&chunk| {
+                let close = generated::close_claim_bitmap::CloseClaimBitmap { chunk };
+                let accounts = generated::close_claim_bitmap::Accounts {
+                    distributor,
+                    claim_bitmap: claim_bitmap_address(&distributor, chunk),
+                    funder: authority.address,
+                };
+                Instruction {
+                    program_id: ID,
+                    accounts: accounts.to_account_metas(None),
+                    data: close.data(),
+                }
+            })
+            .collect();
+
+        let signature = send_as_authority(program, authority, instructions)
+            .context("Failed to send close_claim_bitmap transaction")?;
+
+        println!("✓ Closed {} claim bitmaps", batch.len());
+        println!("  Transaction: {}", signature);
+    }
+
+    let close = generated::close_distributor::CloseDistributor {};
+    let accounts = generated::close_distributor::Accounts {
+        mint,
+        distributor,
+        vault: token_account_address(&distributor, &mint),
+        token_account: token_account_address(&authority.address, &mint),
+        deny_entry: denylist_address(&mint, &authority.address),
+        funder: authority.address,
+    };
+
+    let instruction = Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: close.data(),
+    };
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send close_distributor transaction")?;
+
+    println!("✓ Distributor closed");
+    println!("  Distributor address: {}", distributor);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+// Maximum number of close instructions packed into a single transaction
+const CLOSE_ACCOUNTS_PER_TX: usize = 10;
+
+fn close_account_instruction(owner: Pubkey, mint: Pubkey, destination: Pubkey) -> Instruction {
+    let close = generated::close_token_account::CloseTokenAccount {};
+    let accounts = generated::close_token_account::Accounts {
+        mint,
+        token_account: token_account_address(&owner, &mint),
+        destination,
+        owner,
+    };
+
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: close.data(),
+    }
+}
+
+pub fn close_account(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    mint: Pubkey,
+    destination: Pubkey,
+) -> Result<()> {
+    let instruction = close_account_instruction(authority.address, mint, destination);
+
+    let signature = send_as_authority(program, authority, vec![instruction])
+        .context("Failed to send close_token_account transaction")?;
+
+    println!("✓ Token account closed");
+    println!("  Mint address: {}", mint);
+    println!("  Rent returned to: {}", destination);
+    println!("  Transaction: {}", signature);
+
+    Ok(())
+}
+
+pub fn close_empty_accounts(
+    program: &Program<Rc<Keypair>>,
+    authority: &Authority,
+    destination: Pubkey,
+) -> Result<()> {
+    // The owner is the first field after the 8-byte discriminator.
+    let filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        8,
+        authority.address.as_ref(),
+    ))];
+    let empty_accounts: Vec<TokenAccount> = program
+        .accounts::<TokenAccount>(filters)
+        .context("Failed to fetch token accounts")?
+        .into_iter()
+        .map(|(_, account)| account)
+        .filter(|account| account.amount == 0)
+        .collect();
+
+    if empty_accounts.is_empty() {
+        println!("No empty token accounts found for {}", authority.address);
+        return Ok(());
+    }
+
+    // Closing a frozen account would fail the whole batch.
+    let (skipped, to_close): (Vec<TokenAccount>, Vec<TokenAccount>) = empty_accounts
+        .into_iter()
+        .partition(|account| account.state == AccountState::Frozen);
+    for account in &skipped {
+        println!("  Skipping account for mint {}: frozen", account.mint);
+    }
+
+    for chunk in to_close.chunks(CLOSE_ACCOUNTS_PER_TX) {
+        let instructions = chunk
+            .iter()
+            .map(|account| close_account_instruction(account.owner, account.mint, destination))
+            .collect();
+
+        let signature = send_as_authority(program, authority, instructions)
+            .context("Failed to send close_token_account transaction")?;
+
+        for account in chunk {
+            println!("✓ Closed token account for mint {}", account.mint);
+        }
+        println!("  Transaction: {}", signature);
+    }
+
+    println!(
+        "✓ Closed {} empty token accounts ({} skipped)",
+        to_close.len(),
+        skipped.len()
+    );
+    println!("  Rent returned to: {}", destination);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_client::solana_sdk::transaction::Transaction;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_load_keypair_valid() {
+        let keypair = Keypair::new();
+        let keypair_bytes = keypair.to_bytes();
+        let json = serde_json::to_string(&keypair_bytes.to_vec()).unwrap();
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(json.as_bytes()).unwrap();
+        temp_file.flush().unwrap();
+
+        let loaded = load_keypair(temp_file.path().to_str().unwrap()).unwrap();
+        assert_eq!(loaded.pubkey(), keypair.pubkey());
+    }
+
+    #[test]
+    fn test_load_keypair_invalid_path() {
+        let result = load_keypair("/nonexistent/path/keypair.json");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_pubkey() {
+        let pubkey = Pubkey::new_unique();
+        assert_eq!(parse_pubkey(&pubkey.to_string()).unwrap(), pubkey);
+        assert!(parse_pubkey("not-a-pubkey").is_err());
+    }
+
+    #[test]
+    fn test_format_optional() {
+        let pubkey = Pubkey::new_unique();
+        assert_eq!(format_optional(Some(pubkey)), pubkey.to_string());
+        assert_eq!(format_optional(Some(42u64)), "42");
+        assert_eq!(format_optional::<u64>(None), "none");
+    }
+
+    #[test]
+    fn test_token_account_address() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+
+        let (expected, _) =
+            Pubkey::find_program_address(&[b"token", owner.as_ref(), mint.as_ref()], &ID);
+        assert_eq!(token_account_address(&owner, &mint), expected);
+        assert_ne!(token_account_address(&mint, &owner), expected);
+    }
+
+    #[test]
+    fn test_parse_owner_list() {
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+        let contents = format!(
+            "owner,name\n{},alice\n\n# pending review\n  {}  \n",
+            first, second
+        );
+
+        assert_eq!(parse_owner_list(&contents).unwrap(), vec![first, second]);
+        assert!(parse_owner_list("owner\nnot-a-pubkey\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_recipient_list() {
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+        let contents = format!("owner,amount\n{},100\n# bonus\n{}, 25\n", first, second);
+
+        assert_eq!(
+            parse_recipient_list(&contents).unwrap(),
+            vec![(first, 100), (second, 25)]
+        );
+        assert!(parse_recipient_list(&format!("{}\n", first)).is_err());
+        assert!(parse_recipient_list(&format!("{},-5\n", first)).is_err());
+    }
+
+    #[test]
+    fn test_pda_addresses() {
+        let mint = Pubkey::new_unique();
+        let wallet = Pubkey::new_unique();
+        let id = 7u64.to_le_bytes();
+
+        let cases: [(Pubkey, &[&[u8]]); 7] = [
+            (
+                denylist_address(&mint, &wallet),
+                &[b"deny", mint.as_ref(), wallet.as_ref()],
+            ),
+            (metadata_address(&mint), &[b"metadata", mint.as_ref()]),
+            (
+                minter_role_address(&mint, &wallet),
+                &[b"minter", mint.as_ref(), wallet.as_ref()],
+            ),
+            (
+                pending_mint_address(&mint, 7),
+                &[b"pending_mint", mint.as_ref(), &id],
+            ),
+            (offer_address(&wallet, 7), &[b"offer", wallet.as_ref(), &id]),
+            (vesting_address(&mint, 7), &[b"vesting", mint.as_ref(), &id]),
+            (
+                permit_nonce_address(&wallet, &mint, 7),
+                &[b"permit", wallet.as_ref(), mint.as_ref(), &id],
+            ),
+        ];
+        for (address, seeds) in &cases {
+            assert_eq!(*address, Pubkey::find_program_address(seeds, &ID).0);
+        }
+
+        // The seed prefixes keep every kind of account apart for the same inputs.
+        let addresses: std::collections::HashSet<_> =
+            cases.iter().map(|(address, _)| address).collect();
+        assert_eq!(addresses.len(), cases.len());
+    }
+
+    #[test]
+    fn test_transfer_many_batches() {
+        let payer = Keypair::new();
+        let authority = Authority::payer(&payer);
+        let mint = Pubkey::new_unique();
+        let recipients: Vec<(Pubkey, u64)> =
+            (0..30).map(|_| (Pubkey::new_unique(), u64::MAX)).collect();
+
+        // Size of the signed transaction as sent over the wire.
+        let serialized_len = |authority: &Authority, batch: &[(Pubkey, u64)]| {
+            let mut instruction = transfer_many_instruction(authority, mint, batch);
+            instruction.accounts.extend(authority.signer_metas());
+            let mut signers: Vec<&Keypair> = vec![&payer];
+            signers.extend(&authority.signers);
+            let transaction = Transaction::new_signed_with_payer(
+                &[instruction],
+                Some(&payer.pubkey()),
+                &signers,
+                Hash::new_unique(),
+            );
+            bincode::serialize(&transaction).unwrap().len()
+        };
+
+        // Two accounts per recipient leave room for 12 payouts in a transaction.
+        let batches = transfer_many_batches(&payer.pubkey(), &authority, mint, &recipients);
+        assert_eq!(
+            batches.iter().map(|batch| batch.len()).collect::<Vec<_>>(),
+            vec![12, 12, 6]
+        );
+        assert!(serialized_len(&authority, batches[0]) <= PACKET_DATA_SIZE);
+        assert!(serialized_len(&authority, &recipients[..13]) > PACKET_DATA_SIZE);
+
+        // Each multisig member signing takes up room as well.
+        let multisig =
+            Authority::multisig(Pubkey::new_unique(), vec![Keypair::new(), Keypair::new()]);
+        let batches = transfer_many_batches(&payer.pubkey(), &multisig, mint, &recipients);
+        let len = batches[0].len();
+        assert!(len < 12);
+        assert!(serialized_len(&multisig, batches[0]) <= PACKET_DATA_SIZE);
+        assert!(serialized_len(&multisig, &recipients[..=len]) > PACKET_DATA_SIZE);
+        assert_eq!(batches.iter().map(|batch| batch.len()).sum::<usize>(), 30);
+    }
+
+    #[test]
+    fn test_build_merkle_tree() {
+        let recipients: Vec<(Pubkey, u64)> = (1..=5)
+            .map(|amount| (Pubkey::new_unique(), amount * 100))
+            .collect();
+
+        let (root, proofs) = build_merkle_tree(&recipients);
+        for (index, ((claimant, amount), proof)) in recipients.iter().zip(&proofs).enumerate() {
+            let leaf = airdrop_leaf(index as u64, claimant, *amount);
+            assert!(solana_token::verify_merkle_proof(proof, &root, leaf));
+
+            // The proof only holds for the exact index and amount.
+            let wrong_amount = airdrop_leaf(index as u64, claimant, amount + 1);
+            assert!(!solana_token::verify_merkle_proof(
+                proof,
+                &root,
+                wrong_amount
+            ));
+            let wrong_index = airdrop_leaf(index as u64 + 1, claimant, *amount);
+            assert!(!solana_token::verify_merkle_proof(
+                proof,
+                &root,
+                wrong_index
+            ));
+        }
+
+        let (single_root, single_proofs) = build_merkle_tree(&recipients[..1]);
+        assert_eq!(single_root, airdrop_leaf(0, &recipients[0].0, 100));
+        assert!(single_proofs[0].is_empty());
+    }
+
+    #[test]
+    fn test_airdrop_round_trip() {
+        let mint = Pubkey::new_unique();
+        let recipients = vec![(Pubkey::new_unique(), 10), (Pubkey::new_unique(), 20)];
+
+        let airdrop = Airdrop::new(mint, &recipients);
+        assert_eq!(airdrop.total_amount, 30);
+        assert_eq!(airdrop.claims[1].claimant, recipients[1].0.to_string());
+
+        let json = serde_json::to_string(&airdrop).unwrap();
+        let parsed: Airdrop = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, airdrop);
+
+        let (parsed_mint, distributor, root) = parsed.distributor().unwrap();
+        assert_eq!(parsed_mint, mint);
+        assert_eq!(root, build_merkle_tree(&recipients).0);
+        assert_eq!(distributor, distributor_address(&mint, &root));
+    }
+
+    #[test]
+    fn test_signed_permit_instructions() {
+        let owner = Keypair::new();
+        let payer = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let spender = Pubkey::new_unique();
+
+        let permit = SignedPermit::sign(&owner, mint, spender, 500, 1, 1_700_000_000);
+        let json = serde_json::to_string(&permit).unwrap();
+        let permit: SignedPermit = serde_json::from_str(&json).unwrap();
+
+        let instructions = permit.instructions(&payer).unwrap();
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(
+            instructions[0].program_id,
+            anchor_client::solana_sdk::ed25519_program::ID
+        );
+        let message = permit_message(&mint, &owner.pubkey(), &spender, 500, 1, 1_700_000_000);
+        assert!(instructions[0].data.ends_with(&message));
+        assert_eq!(instructions[1].program_id, ID);
+
+        let tampered = SignedPermit {
+            amount: 5_000,
+            ..permit
+        };
+        assert!(tampered.instructions(&payer).is_err());
+    }
+
+    #[test]
+    fn test_authority_signer_metas() {
+        let payer = Keypair::new();
+        let authority = Authority::payer(&payer);
+        assert_eq!(authority.address, payer.pubkey());
+        assert!(authority.signer_metas().is_empty());
+
+        let multisig = Pubkey::new_unique();
+        let members = vec![Keypair::new(), Keypair::new()];
+        let expected: Vec<Pubkey> = members.iter().map(|member| member.pubkey()).collect();
+        let authority = Authority::multisig(multisig, members);
+        assert_eq!(authority.address, multisig);
+
+        let metas = authority.signer_metas();
+        assert_eq!(
+            metas.iter().map(|meta| meta.pubkey).collect::<Vec<_>>(),
+            expected
+        );
+        assert!(metas.iter().all(|meta| meta.is_signer && !meta.is_writable));
     }
 }
@@ -1,8 +1,24 @@
-use anchor_client::{solana_sdk::commitment_config::CommitmentConfig, Client, Cluster};
-use anyhow::Result;
+use anchor_client::{
+    solana_sdk::{commitment_config::CommitmentConfig, signature::Signer},
+    Client, Cluster,
+};
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
-use solana_token_cli::{init, load_keypair, ID};
-use std::rc::Rc;
+use solana_token_cli::{
+    accept_mint_authority, add_minter, add_to_denylist, airdrop_build, airdrop_claim,
+    airdrop_close, airdrop_create, apply_mint_delay, approve, cancel_mint,
+    cancel_mint_authority_transfer, cancel_offer, cancel_permit, claim_vested, clawback,
+    close_account, close_empty_accounts, create_multisig, create_vesting, execute_mint,
+    freeze_account, harvest_fees, init, list_denylist, list_offers, list_vesting, load_keypair,
+    make_offer, mint_info, mint_to_many, mint_tokens, parse_owner_list, parse_pubkey,
+    parse_recipient_list, pause, propose_mint_authority, queue_mint, remove_from_denylist,
+    remove_metadata_field, remove_minter, revoke, revoke_mint_authority, revoke_vesting,
+    set_metadata_field, set_metadata_update_authority, set_mint_authority, set_mint_delay,
+    set_mint_rate_limit, set_minter_allowance, set_transfer_fee, sign_permit, submit_permit,
+    take_offer, thaw_account, thaw_accounts, transfer_many, unpause, update_metadata,
+    withdraw_fees, Authority, MetadataArgs, MintOptions, VestingSchedule, ID,
+};
+use std::{fs, rc::Rc};
 
 #[derive(Parser)]
 #[command(name = "solana-token-cli")]
@@ -16,6 +32,14 @@ struct Cli {
     #[arg(short, long, default_value = "~/.config/solana/id.json")]
     keypair: String,
 
+    /// Multisig to act as in place of the payer for mint, freeze, metadata and owner authorities
+    #[arg(long, global = true)]
+    multisig: Option<String>,
+
+    /// Keypair file of a multisig member approving the transaction (repeat for each member)
+    #[arg(long = "multisig-signer", global = true, requires = "multisig")]
+    multisig_signers: Vec<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -31,6 +55,46 @@ enum Commands {
         /// Path to mint keypair (will generate new if not provided)
         #[arg(short, long)]
         mint_keypair: Option<String>,
+
+        /// Address allowed to freeze and thaw token accounts (none if not provided)
+        #[arg(short, long)]
+        freeze_authority: Option<String>,
+
+        /// Maximum total supply in base units (uncapped if not provided)
+        #[arg(long)]
+        max_supply: Option<u64>,
+
+        /// Address allowed to pause all token movement (none if not provided)
+        #[arg(short, long)]
+        pause_authority: Option<String>,
+
+        /// Address allowed to set and collect transfer fees (none if not provided)
+        #[arg(long)]
+        fee_authority: Option<String>,
+
+        /// Make tokens non-transferable; they can only be minted and burned
+        #[arg(long)]
+        non_transferable: bool,
+
+        /// Address allowed to transfer or burn from any token account (none if not provided)
+        #[arg(long)]
+        permanent_delegate: Option<String>,
+
+        /// Create token accounts frozen; the freeze authority thaws each approved owner
+        #[arg(long, requires = "freeze_authority")]
+        default_frozen: bool,
+
+        /// Token name (creates a metadata account together with --symbol and --uri)
+        #[arg(long, requires_all = ["symbol", "uri"])]
+        name: Option<String>,
+
+        /// Token symbol
+        #[arg(long, requires = "name")]
+        symbol: Option<String>,
+
+        /// URI pointing to off-chain token metadata
+        #[arg(long, requires = "name")]
+        uri: Option<String>,
     },
     /// Create a token account for a user
     CreateAccount {
@@ -55,6 +119,31 @@ enum Commands {
         /// Amount of tokens to mint
         #[arg(short, long)]
         amount: u64,
+
+        /// Mint against the payer's minter allowance instead of as the mint authority
+        #[arg(long)]
+        as_minter: bool,
+    },
+    /// Mint tokens to every recipient listed in a CSV file
+    MintToMany {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// CSV file of `owner,amount` rows
+        #[arg(long)]
+        from_file: String,
+    },
+    /// Transfer tokens from the payer to every recipient listed in a CSV file, in
+    /// batches sized to fit a transaction (12 recipients each when the payer signs alone)
+    TransferMany {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// CSV file of `owner,amount` rows
+        #[arg(long)]
+        from_file: String,
     },
     /// Transfer tokens between accounts
     Transfer {
@@ -96,6 +185,534 @@ enum Commands {
         #[arg(short, long)]
         mint: String,
     },
+    /// Transfer the mint authority to a new address
+    SetMintAuthority {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Address of the new mint authority
+        #[arg(short, long)]
+        new_authority: String,
+    },
+    /// Permanently revoke the mint authority, fixing the supply
+    RevokeMintAuthority {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+    },
+    /// Propose a new mint authority, which must accept before taking over
+    ProposeMintAuthority {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Address of the proposed mint authority
+        #[arg(short, long)]
+        new_authority: String,
+    },
+    /// Accept a proposed mint authority handover (payer must be the pending authority)
+    AcceptMintAuthority {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+    },
+    /// Cancel a pending mint authority handover
+    CancelMintAuthorityTransfer {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+    },
+    /// Freeze a token account (payer must be the freeze authority)
+    Freeze {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Owner of the token account to freeze
+        #[arg(short, long)]
+        owner: String,
+    },
+    /// Thaw a frozen token account (payer must be the freeze authority)
+    Thaw {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Owner of the token account to thaw
+        #[arg(short, long, required_unless_present = "from_file")]
+        owner: Option<String>,
+
+        /// CSV file of approved owners to thaw, one address per line in the first column
+        #[arg(long, conflicts_with = "owner")]
+        from_file: Option<String>,
+    },
+    /// Allow a delegate to spend up to an amount from the payer's token account
+    Approve {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Address of the delegate
+        #[arg(short, long)]
+        delegate: String,
+
+        /// Maximum amount the delegate may transfer or burn
+        #[arg(short, long)]
+        amount: u64,
+    },
+    /// Sign a permit letting a spender use the payer's tokens, for anyone to submit later
+    SignPermit {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Address allowed to spend the tokens
+        #[arg(short, long)]
+        spender: String,
+
+        /// Maximum amount the spender may transfer or burn
+        #[arg(short, long)]
+        amount: u64,
+
+        /// Number used once per owner; a permit with a used nonce is rejected
+        #[arg(short, long)]
+        nonce: u64,
+
+        /// Seconds until the permit can no longer be submitted
+        #[arg(short, long, default_value_t = 3600)]
+        expires_in: i64,
+
+        /// File to write the signed permit to
+        #[arg(short, long, default_value = "permit.json")]
+        output: String,
+    },
+    /// Submit a signed permit, paying its fees as the payer
+    SubmitPermit {
+        /// File containing the signed permit
+        #[arg(short, long, default_value = "permit.json")]
+        file: String,
+    },
+    /// Use up a permit nonce so a permit signed with it can no longer be submitted
+    CancelPermit {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Nonce of the permit to cancel
+        #[arg(short, long)]
+        nonce: u64,
+    },
+    /// Remove the delegate from the payer's token account
+    Revoke {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+    },
+    /// Pause all minting, transfers and burns (payer must be the pause authority)
+    Pause {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+    },
+    /// Resume a paused mint (payer must be the pause authority)
+    Unpause {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+    },
+    /// Close the payer's empty token account and reclaim its rent
+    CloseAccount {
+        /// Address of the token mint (required unless --all-empty is set)
+        #[arg(short, long, required_unless_present = "all_empty")]
+        mint: Option<String>,
+
+        /// Close every empty token account owned by the payer
+        #[arg(long, conflicts_with = "mint")]
+        all_empty: bool,
+
+        /// Address receiving the reclaimed rent (defaults to payer if not provided)
+        #[arg(short, long)]
+        destination: Option<String>,
+    },
+    /// Update the token name, symbol or URI (payer must be the metadata update authority)
+    UpdateMetadata {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// New token name
+        #[arg(long)]
+        name: Option<String>,
+
+        /// New token symbol
+        #[arg(long)]
+        symbol: Option<String>,
+
+        /// New URI pointing to off-chain token metadata
+        #[arg(long)]
+        uri: Option<String>,
+    },
+    /// Add or update an additional metadata field
+    SetMetadataField {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Field key
+        #[arg(long)]
+        key: String,
+
+        /// Field value
+        #[arg(long)]
+        value: String,
+    },
+    /// Remove an additional metadata field
+    RemoveMetadataField {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Field key
+        #[arg(long)]
+        key: String,
+    },
+    /// Change the metadata update authority, or make the metadata immutable
+    SetMetadataUpdateAuthority {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Address of the new update authority
+        #[arg(short, long, required_unless_present = "immutable")]
+        new_authority: Option<String>,
+
+        /// Permanently remove the update authority
+        #[arg(long, conflicts_with = "new_authority")]
+        immutable: bool,
+    },
+    /// Set the transfer fee (payer must be the fee authority)
+    SetTransferFee {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Fee rate in basis points (1/100 of a percent, at most 10000)
+        #[arg(short, long)]
+        basis_points: u16,
+
+        /// Maximum fee charged per transfer, in base units
+        #[arg(long)]
+        maximum_fee: u64,
+    },
+    /// Move withheld fees from every token account of the mint onto the mint
+    HarvestFees {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+    },
+    /// Withdraw harvested fees into a token account
+    WithdrawFees {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Owner of the receiving token account (defaults to payer if not provided)
+        #[arg(short, long)]
+        owner: Option<String>,
+    },
+    /// Seize tokens from any holder (payer must be the permanent delegate)
+    Clawback {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Owner of the token account to take tokens from
+        #[arg(short, long)]
+        from: String,
+
+        /// Owner of the token account receiving the tokens (burns them if not provided)
+        #[arg(short, long)]
+        to: Option<String>,
+
+        /// Amount of tokens to claw back
+        #[arg(short, long)]
+        amount: u64,
+    },
+    /// Block a wallet from minting, transferring or burning (payer must be the freeze authority)
+    DenylistAdd {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Wallet to block
+        #[arg(short, long)]
+        wallet: String,
+    },
+    /// Unblock a denylisted wallet (payer must be the freeze authority)
+    DenylistRemove {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Wallet to unblock
+        #[arg(short, long)]
+        wallet: String,
+    },
+    /// List every denylisted wallet of a mint
+    Denylist {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+    },
+    /// Let an address mint up to an allowance without the mint authority
+    AddMinter {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Address of the minter
+        #[arg(long)]
+        minter: String,
+
+        /// Tokens the minter may mint, in base units
+        #[arg(short, long)]
+        allowance: u64,
+    },
+    /// Replace the remaining allowance of a minter
+    SetMinterAllowance {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Address of the minter
+        #[arg(long)]
+        minter: String,
+
+        /// New allowance, in base units
+        #[arg(short, long)]
+        allowance: u64,
+    },
+    /// Revoke a minter's role
+    RemoveMinter {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Address of the minter
+        #[arg(long)]
+        minter: String,
+    },
+    /// Change the delay queued mints must wait before they can be executed
+    SetMintDelay {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Delay in seconds; while non-zero, tokens can only be minted through queue-mint
+        #[arg(short, long)]
+        seconds: i64,
+    },
+    /// Apply a shorter mint delay once the current delay has passed since setting it
+    ApplyMintDelay {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+    },
+    /// Cap how many tokens can be minted per time window
+    SetMintRateLimit {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Most tokens that can be minted per window, in base units
+        #[arg(short, long)]
+        limit: u64,
+
+        /// Length of the window in seconds
+        #[arg(short, long, default_value_t = 86400)]
+        window_seconds: i64,
+    },
+    /// Queue a mint that can be executed once the mint delay has passed
+    QueueMint {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Owner address to mint tokens to
+        #[arg(short, long)]
+        to: String,
+
+        /// Amount of tokens to mint
+        #[arg(short, long)]
+        amount: u64,
+    },
+    /// Execute a queued mint whose delay has passed
+    ExecuteMint {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Id of the queued mint, as shown by queue-mint and mint-info
+        #[arg(short, long)]
+        id: u64,
+    },
+    /// Cancel a queued mint
+    CancelMint {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Id of the queued mint, as shown by queue-mint and mint-info
+        #[arg(short, long)]
+        id: u64,
+    },
+    /// Lock tokens from the payer's account that unlock for a beneficiary over time
+    CreateVesting {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Owner address the tokens vest to
+        #[arg(short, long)]
+        beneficiary: String,
+
+        /// Amount of tokens to vest
+        #[arg(short, long)]
+        amount: u64,
+
+        /// Unix timestamp vesting starts from (now if not provided)
+        #[arg(long)]
+        start: Option<i64>,
+
+        /// Seconds after the start before any tokens can be claimed
+        #[arg(short, long, default_value_t = 0)]
+        cliff_seconds: i64,
+
+        /// Seconds after the start until all tokens have vested
+        #[arg(short, long)]
+        duration_seconds: i64,
+
+        /// Let the payer take back the unvested tokens later
+        #[arg(short, long)]
+        revocable: bool,
+    },
+    /// Claim the vested tokens of a schedule into the payer's account
+    ClaimVested {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Id of the vesting schedule, as shown by create-vesting and vesting
+        #[arg(short, long)]
+        id: u64,
+    },
+    /// End a revocable schedule, returning the unvested tokens to the payer
+    RevokeVesting {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Id of the vesting schedule, as shown by create-vesting and vesting
+        #[arg(short, long)]
+        id: u64,
+    },
+    /// List vesting schedules of a mint with their claimable balances
+    Vesting {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// Only list schedules vesting to this owner
+        #[arg(short, long)]
+        beneficiary: Option<String>,
+    },
+    /// Offer tokens from the payer's account in exchange for tokens of another mint
+    MakeOffer {
+        /// Address of the token mint to offer
+        #[arg(short, long)]
+        mint: String,
+
+        /// Amount of tokens to offer, held in escrow until taken or cancelled
+        #[arg(short, long)]
+        amount: u64,
+
+        /// Address of the token mint requested in return
+        #[arg(long)]
+        request_mint: String,
+
+        /// Amount of tokens requested in return
+        #[arg(long)]
+        request_amount: u64,
+    },
+    /// Take an offer, paying the requested tokens from the payer's account
+    TakeOffer {
+        /// Address of the offer, as shown by make-offer and offers
+        #[arg(short, long)]
+        offer: String,
+    },
+    /// Cancel an offer made by the payer, returning the escrowed tokens
+    CancelOffer {
+        /// Address of the offer, as shown by make-offer and offers
+        #[arg(short, long)]
+        offer: String,
+    },
+    /// List open offers
+    Offers {
+        /// Only list offers offering or requesting this mint
+        #[arg(short, long)]
+        mint: Option<String>,
+    },
+    /// Build a merkle airdrop file from a CSV of recipients
+    AirdropBuild {
+        /// Address of the token mint
+        #[arg(short, long)]
+        mint: String,
+
+        /// CSV file of `owner,amount` rows
+        #[arg(long)]
+        from_file: String,
+
+        /// File to write the airdrop, with every recipient's proof, to
+        #[arg(short, long, default_value = "airdrop.json")]
+        output: String,
+    },
+    /// Create and fund the distributor for an airdrop file from the payer's account
+    AirdropCreate {
+        /// Airdrop file written by airdrop-build
+        #[arg(short, long)]
+        file: String,
+
+        /// Seconds during which the airdrop can be claimed
+        #[arg(short, long)]
+        duration_seconds: i64,
+    },
+    /// Submit the unclaimed claims of an airdrop file
+    AirdropClaim {
+        /// Airdrop file written by airdrop-build
+        #[arg(short, long)]
+        file: String,
+
+        /// Only claim for this recipient
+        #[arg(short, long)]
+        claimant: Option<String>,
+    },
+    /// Return an ended airdrop's unclaimed tokens and rent to the funder
+    AirdropClose {
+        /// Airdrop file written by airdrop-build
+        #[arg(short, long)]
+        file: String,
+    },
+    /// Create an M-of-N multisig usable as a mint, freeze or owner authority
+    CreateMultisig {
+        /// Number of member signatures required to act as the multisig
+        #[arg(short, long)]
+        threshold: u8,
+
+        /// Address of a member (repeat for each member)
+        #[arg(short, long = "signer", required = true)]
+        signers: Vec<String>,
+    },
 }
 
 fn main() -> Result<()> {
@@ -110,23 +727,84 @@ fn main() -> Result<()> {
     let client = Client::new_with_options(cluster, payer.clone(), CommitmentConfig::confirmed());
     let program = client.program(ID)?;
 
+    // Mint, freeze and owner authorities default to the payer
+    let authority = match &cli.multisig {
+        Some(multisig) => Authority::multisig(
+            parse_pubkey(multisig)?,
+            cli.multisig_signers
+                .iter()
+                .map(|path| load_keypair(path))
+                .collect::<Result<_>>()?,
+        ),
+        None => Authority::payer(&payer),
+    };
+
     match cli.command {
         Commands::Init {
             decimals,
             mint_keypair,
+            freeze_authority,
+            max_supply,
+            pause_authority,
+            fee_authority,
+            non_transferable,
+            permanent_delegate,
+            default_frozen,
+            name,
+            symbol,
+            uri,
         } => {
-            init(&program, &payer, decimals, mint_keypair)?;
+            let options = MintOptions {
+                mint_keypair,
+                freeze_authority: freeze_authority.as_deref().map(parse_pubkey).transpose()?,
+                max_supply,
+                pause_authority: pause_authority.as_deref().map(parse_pubkey).transpose()?,
+                fee_authority: fee_authority.as_deref().map(parse_pubkey).transpose()?,
+                non_transferable,
+                permanent_delegate: permanent_delegate
+                    .as_deref()
+                    .map(parse_pubkey)
+                    .transpose()?,
+                default_frozen,
+                metadata: name.map(|name| MetadataArgs {
+                    name,
+                    symbol: symbol.unwrap_or_default(),
+                    uri: uri.unwrap_or_default(),
+                }),
+            };
+            init(&program, &payer, decimals, options)?;
         }
         Commands::CreateAccount { mint, owner } => {
             println!("TODO: implement create-account command");
             println!("  mint: {}", mint);
             println!("  owner: {:?}", owner);
         }
-        Commands::Mint { mint, to, amount } => {
-            println!("TODO: implement mint command");
-            println!("  mint: {}", mint);
-            println!("  to: {}", to);
-            println!("  amount: {}", amount);
+        Commands::Mint {
+            mint,
+            to,
+            amount,
+            as_minter,
+        } => {
+            mint_tokens(
+                &program,
+                &authority,
+                parse_pubkey(&mint)?,
+                parse_pubkey(&to)?,
+                amount,
+                as_minter,
+            )?;
+        }
+        Commands::MintToMany { mint, from_file } => {
+            let contents = fs::read_to_string(&from_file)
+                .with_context(|| format!("Failed to read {}", from_file))?;
+            let recipients = parse_recipient_list(&contents)?;
+            mint_to_many(&program, &authority, parse_pubkey(&mint)?, &recipients)?;
+        }
+        Commands::TransferMany { mint, from_file } => {
+            let contents = fs::read_to_string(&from_file)
+                .with_context(|| format!("Failed to read {}", from_file))?;
+            let recipients = parse_recipient_list(&contents)?;
+            transfer_many(&program, &authority, parse_pubkey(&mint)?, &recipients)?;
         }
         Commands::Transfer { mint, to, amount } => {
             println!("TODO: implement transfer command");
@@ -145,8 +823,389 @@ fn main() -> Result<()> {
             println!("  owner: {:?}", owner);
         }
         Commands::MintInfo { mint } => {
-            println!("TODO: implement mint-info command");
-            println!("  mint: {}", mint);
+            mint_info(&program, parse_pubkey(&mint)?)?;
+        }
+        Commands::SetMintAuthority {
+            mint,
+            new_authority,
+        } => {
+            set_mint_authority(
+                &program,
+                &authority,
+                parse_pubkey(&mint)?,
+                parse_pubkey(&new_authority)?,
+            )?;
+        }
+        Commands::RevokeMintAuthority { mint } => {
+            revoke_mint_authority(&program, &authority, parse_pubkey(&mint)?)?;
+        }
+        Commands::ProposeMintAuthority {
+            mint,
+            new_authority,
+        } => {
+            propose_mint_authority(
+                &program,
+                &authority,
+                parse_pubkey(&mint)?,
+                parse_pubkey(&new_authority)?,
+            )?;
+        }
+        Commands::AcceptMintAuthority { mint } => {
+            accept_mint_authority(&program, &authority, parse_pubkey(&mint)?)?;
+        }
+        Commands::CancelMintAuthorityTransfer { mint } => {
+            cancel_mint_authority_transfer(&program, &authority, parse_pubkey(&mint)?)?;
+        }
+        Commands::Freeze { mint, owner } => {
+            freeze_account(
+                &program,
+                &authority,
+                parse_pubkey(&mint)?,
+                parse_pubkey(&owner)?,
+            )?;
+        }
+        Commands::Thaw {
+            mint,
+            owner,
+            from_file,
+        } => match (owner, from_file) {
+            (Some(owner), _) => {
+                thaw_account(
+                    &program,
+                    &authority,
+                    parse_pubkey(&mint)?,
+                    parse_pubkey(&owner)?,
+                )?;
+            }
+            (None, Some(path)) => {
+                let contents = fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path))?;
+                let owners = parse_owner_list(&contents)?;
+                thaw_accounts(&program, &authority, parse_pubkey(&mint)?, &owners)?;
+            }
+            (None, None) => unreachable!("clap requires --owner or --from-file"),
+        },
+        Commands::Approve {
+            mint,
+            delegate,
+            amount,
+        } => {
+            approve(
+                &program,
+                &authority,
+                parse_pubkey(&mint)?,
+                parse_pubkey(&delegate)?,
+                amount,
+            )?;
+        }
+        Commands::SignPermit {
+            mint,
+            spender,
+            amount,
+            nonce,
+            expires_in,
+            output,
+        } => {
+            sign_permit(
+                &payer,
+                parse_pubkey(&mint)?,
+                parse_pubkey(&spender)?,
+                amount,
+                nonce,
+                expires_in,
+                &output,
+            )?;
+        }
+        Commands::SubmitPermit { file } => {
+            submit_permit(&program, &payer, &file)?;
+        }
+        Commands::CancelPermit { mint, nonce } => {
+            cancel_permit(&program, &payer, &authority, parse_pubkey(&mint)?, nonce)?;
+        }
+        Commands::Revoke { mint } => {
+            revoke(&program, &authority, parse_pubkey(&mint)?)?;
+        }
+        Commands::Pause { mint } => {
+            pause(&program, &payer, parse_pubkey(&mint)?)?;
+        }
+        Commands::Unpause { mint } => {
+            unpause(&program, &payer, parse_pubkey(&mint)?)?;
+        }
+        Commands::CloseAccount {
+            mint,
+            all_empty,
+            destination,
+        } => {
+            let destination = match destination {
+                Some(destination) => parse_pubkey(&destination)?,
+                None => payer.pubkey(),
+            };
+            match mint {
+                Some(mint) if !all_empty => {
+                    close_account(&program, &authority, parse_pubkey(&mint)?, destination)?;
+                }
+                _ => close_empty_accounts(&program, &authority, destination)?,
+            }
+        }
+        Commands::UpdateMetadata {
+            mint,
+            name,
+            symbol,
+            uri,
+        } => {
+            update_metadata(
+                &program,
+                &authority,
+                parse_pubkey(&mint)?,
+                name,
+                symbol,
+                uri,
+            )?;
+        }
+        Commands::SetMetadataField { mint, key, value } => {
+            set_metadata_field(&program, &authority, parse_pubkey(&mint)?, key, value)?;
+        }
+        Commands::RemoveMetadataField { mint, key } => {
+            remove_metadata_field(&program, &authority, parse_pubkey(&mint)?, key)?;
+        }
+        Commands::SetMetadataUpdateAuthority {
+            mint,
+            new_authority,
+            ..
+        } => {
+            // clap guarantees exactly one of --new-authority or --immutable is set
+            let new_authority = new_authority.as_deref().map(parse_pubkey).transpose()?;
+            set_metadata_update_authority(
+                &program,
+                &authority,
+                parse_pubkey(&mint)?,
+                new_authority,
+            )?;
+        }
+        Commands::SetTransferFee {
+            mint,
+            basis_points,
+            maximum_fee,
+        } => {
+            set_transfer_fee(
+                &program,
+                &payer,
+                parse_pubkey(&mint)?,
+                basis_points,
+                maximum_fee,
+            )?;
+        }
+        Commands::HarvestFees { mint } => {
+            harvest_fees(&program, &payer, parse_pubkey(&mint)?)?;
+        }
+        Commands::WithdrawFees { mint, owner } => {
+            let owner = match owner {
+                Some(owner) => parse_pubkey(&owner)?,
+                None => payer.pubkey(),
+            };
+            withdraw_fees(&program, &payer, parse_pubkey(&mint)?, owner)?;
+        }
+        Commands::Clawback {
+            mint,
+            from,
+            to,
+            amount,
+        } => {
+            clawback(
+                &program,
+                &authority,
+                parse_pubkey(&mint)?,
+                parse_pubkey(&from)?,
+                to.as_deref().map(parse_pubkey).transpose()?,
+                amount,
+            )?;
+        }
+        Commands::DenylistAdd { mint, wallet } => {
+            add_to_denylist(
+                &program,
+                &payer,
+                &authority,
+                parse_pubkey(&mint)?,
+                parse_pubkey(&wallet)?,
+            )?;
+        }
+        Commands::DenylistRemove { mint, wallet } => {
+            remove_from_denylist(
+                &program,
+                &payer,
+                &authority,
+                parse_pubkey(&mint)?,
+                parse_pubkey(&wallet)?,
+            )?;
+        }
+        Commands::Denylist { mint } => {
+            list_denylist(&program, parse_pubkey(&mint)?)?;
+        }
+        Commands::AddMinter {
+            mint,
+            minter,
+            allowance,
+        } => {
+            add_minter(
+                &program,
+                &payer,
+                &authority,
+                parse_pubkey(&mint)?,
+                parse_pubkey(&minter)?,
+                allowance,
+            )?;
+        }
+        Commands::SetMinterAllowance {
+            mint,
+            minter,
+            allowance,
+        } => {
+            set_minter_allowance(
+                &program,
+                &authority,
+                parse_pubkey(&mint)?,
+                parse_pubkey(&minter)?,
+                allowance,
+            )?;
+        }
+        Commands::RemoveMinter { mint, minter } => {
+            remove_minter(
+                &program,
+                &payer,
+                &authority,
+                parse_pubkey(&mint)?,
+                parse_pubkey(&minter)?,
+            )?;
+        }
+        Commands::SetMintDelay { mint, seconds } => {
+            set_mint_delay(&program, &authority, parse_pubkey(&mint)?, seconds)?;
+        }
+        Commands::ApplyMintDelay { mint } => {
+            apply_mint_delay(&program, &authority, parse_pubkey(&mint)?)?;
+        }
+        Commands::SetMintRateLimit {
+            mint,
+            limit,
+            window_seconds,
+        } => {
+            set_mint_rate_limit(
+                &program,
+                &authority,
+                parse_pubkey(&mint)?,
+                limit,
+                window_seconds,
+            )?;
+        }
+        Commands::QueueMint { mint, to, amount } => {
+            queue_mint(
+                &program,
+                &payer,
+                &authority,
+                parse_pubkey(&mint)?,
+                parse_pubkey(&to)?,
+                amount,
+            )?;
+        }
+        Commands::ExecuteMint { mint, id } => {
+            execute_mint(&program, &payer, &authority, parse_pubkey(&mint)?, id)?;
+        }
+        Commands::CancelMint { mint, id } => {
+            cancel_mint(&program, &payer, &authority, parse_pubkey(&mint)?, id)?;
+        }
+        Commands::CreateVesting {
+            mint,
+            beneficiary,
+            amount,
+            start,
+            cliff_seconds,
+            duration_seconds,
+            revocable,
+        } => {
+            create_vesting(
+                &program,
+                &payer,
+                &authority,
+                parse_pubkey(&mint)?,
+                parse_pubkey(&beneficiary)?,
+                amount,
+                VestingSchedule {
+                    start_time: start,
+                    cliff_seconds,
+                    duration_seconds,
+                    revocable,
+                },
+            )?;
+        }
+        Commands::ClaimVested { mint, id } => {
+            claim_vested(&program, &authority, parse_pubkey(&mint)?, id)?;
+        }
+        Commands::RevokeVesting { mint, id } => {
+            revoke_vesting(&program, &authority, parse_pubkey(&mint)?, id)?;
+        }
+        Commands::Vesting { mint, beneficiary } => {
+            list_vesting(
+                &program,
+                parse_pubkey(&mint)?,
+                beneficiary.as_deref().map(parse_pubkey).transpose()?,
+            )?;
+        }
+        Commands::MakeOffer {
+            mint,
+            amount,
+            request_mint,
+            request_amount,
+        } => {
+            make_offer(
+                &program,
+                &payer,
+                &authority,
+                parse_pubkey(&mint)?,
+                amount,
+                parse_pubkey(&request_mint)?,
+                request_amount,
+            )?;
+        }
+        Commands::TakeOffer { offer } => {
+            take_offer(&program, &authority, parse_pubkey(&offer)?)?;
+        }
+        Commands::CancelOffer { offer } => {
+            cancel_offer(&program, &authority, parse_pubkey(&offer)?)?;
+        }
+        Commands::Offers { mint } => {
+            list_offers(&program, mint.as_deref().map(parse_pubkey).transpose()?)?;
+        }
+        Commands::AirdropBuild {
+            mint,
+            from_file,
+            output,
+        } => {
+            let contents = fs::read_to_string(&from_file)
+                .with_context(|| format!("Failed to read {}", from_file))?;
+            let recipients = parse_recipient_list(&contents)?;
+            airdrop_build(parse_pubkey(&mint)?, &recipients, &output)?;
+        }
+        Commands::AirdropCreate {
+            file,
+            duration_seconds,
+        } => {
+            airdrop_create(&program, &payer, &authority, &file, duration_seconds)?;
+        }
+        Commands::AirdropClaim { file, claimant } => {
+            airdrop_claim(
+                &program,
+                &file,
+                claimant.as_deref().map(parse_pubkey).transpose()?,
+            )?;
+        }
+        Commands::AirdropClose { file } => {
+            airdrop_close(&program, &authority, &file)?;
+        }
+        Commands::CreateMultisig { threshold, signers } => {
+            let signers = signers
+                .iter()
+                .map(|signer| parse_pubkey(signer))
+                .collect::<Result<_>>()?;
+            create_multisig(&program, &payer, threshold, signers)?;
         }
     }
 
@@ -11,7 +11,16 @@ fn test_initialize_module_exists() {
     use generated::initialize;
 
     // Create an instance
-    let init = initialize::Initialize { decimals: 9 };
+    let init = initialize::Initialize {
+        decimals: 9,
+        freeze_authority: None,
+        max_supply: None,
+        pause_authority: None,
+        fee_authority: None,
+        non_transferable: false,
+        permanent_delegate: None,
+        default_account_state: generated::AccountState::Initialized,
+    };
 
     // Verify it has the correct discriminator
     assert_eq!(
@@ -154,7 +163,16 @@ fn test_discriminators_are_8_bytes() {
 fn test_instruction_data_serialization() {
     use generated::initialize;
 
-    let init = initialize::Initialize { decimals: 9 };
+    let init = initialize::Initialize {
+        decimals: 9,
+        freeze_authority: None,
+        max_supply: None,
+        pause_authority: None,
+        fee_authority: None,
+        non_transferable: false,
+        permanent_delegate: None,
+        default_account_state: generated::AccountState::Frozen,
+    };
     let data = init.data();
 
     // Data should contain discriminator (8 bytes) + decimals (1 byte) = 9 bytes minimum
@@ -162,4 +180,43 @@ fn test_instruction_data_serialization() {
 
     // First 8 bytes should be the discriminator
     assert_eq!(&data[0..8], initialize::Initialize::DISCRIMINATOR);
+
+    // Enum args serialize as their variant index
+    assert_eq!(data.last(), Some(&1));
+}
+
+#[test]
+fn test_event_decoding() {
+    use anchor_lang::Event;
+    use generated::events;
+
+    let mint = Pubkey::new_unique();
+    let token_account = Pubkey::new_unique();
+
+    // Serialize the event exactly as the program logs it
+    let data = solana_token::TokensMinted {
+        mint,
+        token_account,
+        amount: 1000,
+        total_supply: 5000,
+    }
+    .data();
+
+    assert_eq!(
+        events::decode(&data),
+        Some(events::Event::TokensMinted(events::TokensMinted {
+            mint,
+            token_account,
+            amount: 1000,
+            total_supply: 5000,
+        }))
+    );
+}
+
+#[test]
+fn test_event_decoding_rejects_unknown_data() {
+    use generated::events;
+
+    assert_eq!(events::decode(&[]), None);
+    assert_eq!(events::decode(&[0; 8]), None);
 }
@@ -7,7 +7,7 @@ use anchor_client::{
 };
 use solana_streamer::socket::SocketAddrSpace;
 use solana_test_validator::{TestValidatorGenesis, UpgradeableProgramInfo};
-use solana_token_cli::{init, ID};
+use solana_token_cli::{init, MintOptions, ID};
 use std::path::PathBuf;
 use std::rc::Rc;
 
@@ -50,7 +50,7 @@ fn test_init() {
     let payer = Rc::new(payer);
     let program = setup_program(&validator, payer.clone());
 
-    let result = init(&program, &payer, 9, None);
+    let result = init(&program, &payer, 9, MintOptions::default());
 
     assert!(result.is_ok(), "init failed: {:?}", result.err());
 }
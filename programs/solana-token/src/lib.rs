@@ -1,24 +1,201 @@
 use anchor_lang::prelude::*;
+use solana_instructions_sysvar::{load_current_index_checked, load_instruction_at_checked};
+use solana_sdk_ids::{ed25519_program, sysvar::instructions as instructions_sysvar};
+use solana_sha256_hasher::hashv;
 
 declare_id!("48WQW8ZMQKJhV1FKnGrYVDMEoqc8XutQmvKuqcmRrKux");
 
+pub const MAX_NAME_LEN: usize = 32;
+pub const MAX_SYMBOL_LEN: usize = 10;
+pub const MAX_URI_LEN: usize = 200;
+pub const MAX_METADATA_FIELDS: usize = 8;
+pub const MAX_METADATA_KEY_LEN: usize = 32;
+pub const MAX_METADATA_VALUE_LEN: usize = 64;
+pub const MAX_FEE_BASIS_POINTS: u16 = 10_000;
+pub const MAX_MULTISIG_SIGNERS: usize = 11;
+/// Prefix of every signed permit message, keeping the signature from being valid elsewhere.
+pub const PERMIT_DOMAIN: &[u8] = b"solana-token:permit";
+/// Size of each claim bitmap account's bitmap, kept under the 10 KiB a program can
+/// allocate when creating an account.
+pub const CLAIM_BITMAP_BYTES: usize = 8192;
+pub const CLAIMS_PER_BITMAP: u64 = CLAIM_BITMAP_BYTES as u64 * 8;
+
 #[program]
 pub mod solana_token {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, decimals: u8) -> Result<()> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        decimals: u8,
+        freeze_authority: Option<Pubkey>,
+        max_supply: Option<u64>,
+        pause_authority: Option<Pubkey>,
+        fee_authority: Option<Pubkey>,
+        non_transferable: bool,
+        permanent_delegate: Option<Pubkey>,
+        default_account_state: AccountState,
+    ) -> Result<()> {
+        // Accounts that start frozen could never be thawed without a freeze authority.
+        require!(
+            default_account_state == AccountState::Initialized || freeze_authority.is_some(),
+            ErrorCode::NoFreezeAuthority
+        );
+
         let mint = &mut ctx.accounts.mint;
 
-        mint.authority = ctx.accounts.authority.key();
+        mint.authority = Some(ctx.accounts.authority.key());
         mint.total_supply = 0;
         mint.decimals = decimals;
+        mint.pending_authority = None;
+        mint.freeze_authority = freeze_authority;
+        mint.max_supply = max_supply;
+        mint.pause_authority = pause_authority;
+        mint.paused = false;
+        mint.fee_authority = fee_authority;
+        mint.transfer_fee_basis_points = 0;
+        mint.maximum_fee = 0;
+        mint.withheld_amount = 0;
+        mint.non_transferable = non_transferable;
+        mint.permanent_delegate = permanent_delegate;
+        mint.default_account_state = default_account_state;
+        mint.mint_delay = 0;
+        mint.queued_mint_count = 0;
+        mint.mint_rate_limit = None;
+        mint.mint_rate_window = 0;
+        mint.rate_limit_updated_at = 0;
+        mint.rate_limit_used = 0;
+        mint.vesting_count = 0;
+        mint.pending_mint_delay = None;
+        mint.mint_delay_change_after = 0;
 
         msg!(
             "Token mint initialized! Authority: {}. Decimals: {}",
-            mint.authority,
+            ctx.accounts.authority.key(),
             decimals
         );
 
+        emit!(MintInitialized {
+            mint: mint.key(),
+            authority: ctx.accounts.authority.key(),
+            decimals,
+            freeze_authority,
+            max_supply,
+            pause_authority,
+            fee_authority,
+            non_transferable,
+            permanent_delegate,
+            default_account_state,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_mint_authority(ctx: Context<SetMintAuthority>, new_authority: Pubkey) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let mint = &mut ctx.accounts.mint;
+        let old_authority = mint.authority;
+
+        mint.authority = Some(new_authority);
+        mint.pending_authority = None;
+
+        msg!("Mint authority set to {}", new_authority);
+
+        emit!(MintAuthorityChanged {
+            mint: mint.key(),
+            old_authority,
+            new_authority: mint.authority,
+        });
+
+        Ok(())
+    }
+
+    pub fn revoke_mint_authority(ctx: Context<SetMintAuthority>) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let mint = &mut ctx.accounts.mint;
+
+        // Without an authority nothing can ever be minted again, fixing the supply.
+        let old_authority = mint.authority.take();
+        mint.pending_authority = None;
+
+        msg!(
+            "Mint authority revoked. Supply fixed at {}",
+            mint.total_supply
+        );
+
+        emit!(MintAuthorityChanged {
+            mint: mint.key(),
+            old_authority,
+            new_authority: None,
+        });
+
+        Ok(())
+    }
+
+    pub fn propose_mint_authority(
+        ctx: Context<SetMintAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let mint = &mut ctx.accounts.mint;
+
+        // The handover only completes once the proposed key signs `accept_mint_authority`.
+        mint.pending_authority = Some(new_authority);
+
+        msg!("Mint authority handover proposed to {}", new_authority);
+
+        emit!(MintAuthorityProposed {
+            mint: mint.key(),
+            pending_authority: mint.pending_authority,
+        });
+
+        Ok(())
+    }
+
+    pub fn accept_mint_authority(ctx: Context<AcceptMintAuthority>) -> Result<()> {
+        verify_authority(&ctx.accounts.pending_authority, ctx.remaining_accounts)?;
+
+        let mint = &mut ctx.accounts.mint;
+        let old_authority = mint.authority;
+
+        mint.authority = mint.pending_authority.take();
+
+        msg!(
+            "Mint authority handover accepted by {}",
+            ctx.accounts.pending_authority.key()
+        );
+
+        emit!(MintAuthorityChanged {
+            mint: mint.key(),
+            old_authority,
+            new_authority: mint.authority,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_mint_authority_transfer(ctx: Context<SetMintAuthority>) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let mint = &mut ctx.accounts.mint;
+
+        require!(
+            mint.pending_authority.is_some(),
+            ErrorCode::NoPendingAuthority
+        );
+
+        mint.pending_authority = None;
+
+        msg!("Mint authority handover cancelled");
+
+        emit!(MintAuthorityProposed {
+            mint: mint.key(),
+            pending_authority: None,
+        });
+
         Ok(())
     }
 
@@ -28,25 +205,113 @@ pub mod solana_token {
         token_account.owner = ctx.accounts.owner.key();
         token_account.mint = ctx.accounts.mint.key();
         token_account.amount = 0;
+        // Frozen by default on gated mints until the freeze authority approves the owner.
+        token_account.state = ctx.accounts.mint.default_account_state;
+        token_account.delegate = None;
+        token_account.delegated_amount = 0;
 
         msg!("Token account created for owner: {}", token_account.owner);
 
+        emit!(TokenAccountCreated {
+            token_account: token_account.key(),
+            mint: token_account.mint,
+            owner: token_account.owner,
+            state: token_account.state,
+        });
+
+        Ok(())
+    }
+
+    pub fn close_token_account(ctx: Context<CloseTokenAccount>) -> Result<()> {
+        verify_authority(&ctx.accounts.owner, ctx.remaining_accounts)?;
+
+        let mint = &mut ctx.accounts.mint;
+        let token_account = &mut ctx.accounts.token_account;
+
+        // Only empty accounts can be closed, otherwise the tokens would vanish from supply.
+        require!(token_account.amount == 0, ErrorCode::NonZeroBalance);
+
+        require!(
+            token_account.state != AccountState::Frozen,
+            ErrorCode::AccountFrozen
+        );
+
+        // Withheld fees would be lost with the account, so they are harvested onto the mint.
+        let withheld = token_account.withheld_amount;
+        if withheld > 0 {
+            token_account.withheld_amount = 0;
+            mint.withheld_amount = mint
+                .withheld_amount
+                .checked_add(withheld)
+                .ok_or(ErrorCode::Overflow)?;
+
+            emit!(WithheldHarvested {
+                mint: mint.key(),
+                token_account: token_account.key(),
+                amount: withheld,
+            });
+        }
+
+        msg!(
+            "Closed token account of {}. Rent returned to {}",
+            token_account.owner,
+            ctx.accounts.destination.key()
+        );
+
+        emit!(TokenAccountClosed {
+            token_account: token_account.key(),
+            mint: token_account.mint,
+            owner: token_account.owner,
+            destination: ctx.accounts.destination.key(),
+        });
+
         Ok(())
     }
 
     pub fn mint_tokens(ctx: Context<MintTokens>, amount: u64) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
         let mint = &mut ctx.accounts.mint;
         let token_account = &mut ctx.accounts.token_account;
 
         // Verify the token account belongs to the correct mint.
         require!(token_account.mint == mint.key(), ErrorCode::MintMismatch);
 
+        require!(!mint.paused, ErrorCode::MintPaused);
+
+        // Timelocked mints only mint through `queue_mint` and `execute_mint`.
+        require!(mint.mint_delay == 0, ErrorCode::MintTimelocked);
+
+        require!(
+            token_account.state != AccountState::Frozen,
+            ErrorCode::AccountFrozen
+        );
+
+        require_not_denied(&ctx.accounts.deny_entry)?;
+
+        if let Some(minter_role) = &mut ctx.accounts.minter_role {
+            require!(
+                minter_role.allowance >= amount,
+                ErrorCode::MinterAllowanceExceeded
+            );
+            minter_role.allowance -= amount;
+        }
+
+        consume_mint_capacity(mint, amount)?;
+
         // Update total supply.
         mint.total_supply = mint
             .total_supply
             .checked_add(amount)
             .ok_or(ErrorCode::Overflow)?;
 
+        if let Some(max_supply) = mint.max_supply {
+            require!(
+                mint.total_supply <= max_supply,
+                ErrorCode::MaxSupplyExceeded
+            );
+        }
+
         // Update token account balance.
         token_account.amount = token_account
             .amount
@@ -55,10 +320,90 @@ pub mod solana_token {
 
         msg!("Minted {} tokens to {}", amount, token_account.owner);
 
+        emit!(TokensMinted {
+            mint: mint.key(),
+            token_account: token_account.key(),
+            amount,
+            total_supply: mint.total_supply,
+        });
+
+        Ok(())
+    }
+
+    pub fn mint_to_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MintToMany<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        // Each recipient passes its token account followed by its denylist entry; any
+        // multisig signers come after the last recipient.
+        require!(
+            ctx.remaining_accounts.len() >= amounts.len() * 2,
+            ErrorCode::RecipientAccountsMismatch
+        );
+        let (recipient_accounts, signers) = ctx.remaining_accounts.split_at(amounts.len() * 2);
+
+        verify_authority(&ctx.accounts.authority, signers)?;
+
+        let mint = &mut ctx.accounts.mint;
+
+        require!(!mint.paused, ErrorCode::MintPaused);
+
+        // Timelocked mints only mint through `queue_mint` and `execute_mint`.
+        require!(mint.mint_delay == 0, ErrorCode::MintTimelocked);
+
+        // Update total supply once for the whole batch.
+        let total = amounts
+            .iter()
+            .try_fold(0u64, |total, amount| total.checked_add(*amount))
+            .ok_or(ErrorCode::Overflow)?;
+        consume_mint_capacity(mint, total)?;
+        mint.total_supply = mint
+            .total_supply
+            .checked_add(total)
+            .ok_or(ErrorCode::Overflow)?;
+
+        if let Some(max_supply) = mint.max_supply {
+            require!(
+                mint.total_supply <= max_supply,
+                ErrorCode::MaxSupplyExceeded
+            );
+        }
+
+        let recipients = amounts.len();
+        for (accounts, amount) in recipient_accounts.chunks(2).zip(amounts) {
+            let mut token_account = load_token_account(&accounts[0], mint.key())?;
+
+            require!(
+                token_account.state != AccountState::Frozen,
+                ErrorCode::AccountFrozen
+            );
+
+            require_not_denied_at(&accounts[1], mint.key(), token_account.owner)?;
+
+            token_account.amount = token_account
+                .amount
+                .checked_add(amount)
+                .ok_or(ErrorCode::Overflow)?;
+
+            // Persist right away so a recipient listed twice sees its earlier credit.
+            token_account.exit(&crate::ID)?;
+
+            emit!(TokensMinted {
+                mint: mint.key(),
+                token_account: token_account.key(),
+                amount,
+                total_supply: mint.total_supply,
+            });
+        }
+
+        msg!("Minted {} tokens to {} accounts", total, recipients);
+
         Ok(())
     }
 
     pub fn transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
         let from = &mut ctx.accounts.from;
         let to = &mut ctx.accounts.to;
 
@@ -68,22 +413,174 @@ pub mod solana_token {
         // Verify both accounts belong to the same mint.
         require!(from.mint == to.mint, ErrorCode::MintMismatch);
 
+        require!(!ctx.accounts.mint.paused, ErrorCode::MintPaused);
+
+        require!(
+            !ctx.accounts.mint.non_transferable,
+            ErrorCode::NonTransferable
+        );
+
+        // Frozen accounts can neither send nor receive.
+        require!(
+            from.state != AccountState::Frozen && to.state != AccountState::Frozen,
+            ErrorCode::AccountFrozen
+        );
+
+        let authority = ctx.accounts.authority.key();
+        let clawback = is_permanent_delegate(&ctx.accounts.mint, from, authority);
+        if !clawback {
+            authorize_spend(from, authority, amount)?;
+            // The permanent delegate may still seize funds from a denied wallet.
+            require_not_denied(&ctx.accounts.from_deny_entry)?;
+        }
+        require_not_denied(&ctx.accounts.to_deny_entry)?;
+
+        // The fee is taken out of the transferred amount and held on the recipient's account.
+        let fee = calculate_transfer_fee(&ctx.accounts.mint, amount);
+
         // Update balances.
         from.amount = from.amount.checked_sub(amount).ok_or(ErrorCode::Overflow)?;
 
-        to.amount = to.amount.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        to.amount = to
+            .amount
+            .checked_add(amount - fee)
+            .ok_or(ErrorCode::Overflow)?;
+        to.withheld_amount = to
+            .withheld_amount
+            .checked_add(fee)
+            .ok_or(ErrorCode::Overflow)?;
 
         msg!(
-            "Transferred {} tokens from {} to {}",
+            "Transferred {} tokens from {} to {} (fee {})",
+            amount,
+            from.owner,
+            to.owner,
+            fee
+        );
+
+        emit!(Transferred {
+            mint: from.mint,
+            from: from.key(),
+            to: to.key(),
+            authority,
             amount,
+            fee,
+        });
+
+        if clawback {
+            emit!(ClawedBack {
+                mint: from.mint,
+                token_account: from.key(),
+                permanent_delegate: authority,
+                destination: Some(to.key()),
+                amount,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn transfer_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TransferMany<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        // Each recipient passes its token account followed by its denylist entry; any
+        // multisig signers come after the last recipient. At two accounts per recipient a
+        // transaction fits 12 payouts when the sender also pays the fees.
+        require!(
+            ctx.remaining_accounts.len() >= amounts.len() * 2,
+            ErrorCode::RecipientAccountsMismatch
+        );
+        let (recipient_accounts, signers) = ctx.remaining_accounts.split_at(amounts.len() * 2);
+
+        verify_authority(&ctx.accounts.authority, signers)?;
+
+        let mint = &ctx.accounts.mint;
+        let from = &mut ctx.accounts.from;
+
+        let total = amounts
+            .iter()
+            .try_fold(0u64, |total, amount| total.checked_add(*amount))
+            .ok_or(ErrorCode::Overflow)?;
+
+        // Check sufficient balance.
+        require!(from.amount >= total, ErrorCode::InsufficientFunds);
+
+        require!(!mint.paused, ErrorCode::MintPaused);
+
+        require!(!mint.non_transferable, ErrorCode::NonTransferable);
+
+        require!(from.state != AccountState::Frozen, ErrorCode::AccountFrozen);
+
+        let authority = ctx.accounts.authority.key();
+        let clawback = is_permanent_delegate(mint, from, authority);
+        if !clawback {
+            authorize_spend(from, authority, total)?;
+            // The permanent delegate may still seize funds from a denied wallet.
+            require_not_denied(&ctx.accounts.from_deny_entry)?;
+        }
+
+        // Debit the summed amount once.
+        from.amount = from.amount.checked_sub(total).ok_or(ErrorCode::Overflow)?;
+
+        let recipients = amounts.len();
+        for (accounts, amount) in recipient_accounts.chunks(2).zip(amounts) {
+            // `from` is written back on exit, which would overwrite a credit to itself.
+            require_keys_neq!(accounts[0].key(), from.key(), ErrorCode::SelfTransfer);
+
+            let mut to = load_token_account(&accounts[0], mint.key())?;
+
+            require!(to.state != AccountState::Frozen, ErrorCode::AccountFrozen);
+
+            require_not_denied_at(&accounts[1], mint.key(), to.owner)?;
+
+            // Fees apply to each payout as if it were a separate transfer.
+            let fee = calculate_transfer_fee(mint, amount);
+            to.amount = to
+                .amount
+                .checked_add(amount - fee)
+                .ok_or(ErrorCode::Overflow)?;
+            to.withheld_amount = to
+                .withheld_amount
+                .checked_add(fee)
+                .ok_or(ErrorCode::Overflow)?;
+
+            // Persist right away so a recipient listed twice sees its earlier credit.
+            to.exit(&crate::ID)?;
+
+            emit!(Transferred {
+                mint: from.mint,
+                from: from.key(),
+                to: to.key(),
+                authority,
+                amount,
+                fee,
+            });
+
+            if clawback {
+                emit!(ClawedBack {
+                    mint: from.mint,
+                    token_account: from.key(),
+                    permanent_delegate: authority,
+                    destination: Some(to.key()),
+                    amount,
+                });
+            }
+        }
+
+        msg!(
+            "Transferred {} tokens from {} to {} accounts",
+            total,
             from.owner,
-            to.owner
+            recipients
         );
 
         Ok(())
     }
 
     pub fn burn(ctx: Context<Burn>, amount: u64) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
         let mint = &mut ctx.accounts.mint;
         let token_account = &mut ctx.accounts.token_account;
 
@@ -93,6 +590,20 @@ pub mod solana_token {
         // Verify token accounts belongs to this mint.
         require!(token_account.mint == mint.key(), ErrorCode::MintMismatch);
 
+        require!(!mint.paused, ErrorCode::MintPaused);
+
+        require!(
+            token_account.state != AccountState::Frozen,
+            ErrorCode::AccountFrozen
+        );
+
+        let authority = ctx.accounts.authority.key();
+        let clawback = is_permanent_delegate(mint, token_account, authority);
+        if !clawback {
+            authorize_spend(token_account, authority, amount)?;
+            require_not_denied(&ctx.accounts.deny_entry)?;
+        }
+
         // Update balances.
         token_account.amount = token_account
             .amount
@@ -106,98 +617,3317 @@ pub mod solana_token {
 
         msg!("Burned {} tokens from {}", amount, token_account.owner);
 
+        emit!(Burned {
+            mint: mint.key(),
+            token_account: token_account.key(),
+            authority,
+            amount,
+            total_supply: mint.total_supply,
+        });
+
+        if clawback {
+            emit!(ClawedBack {
+                mint: mint.key(),
+                token_account: token_account.key(),
+                permanent_delegate: authority,
+                destination: None,
+                amount,
+            });
+        }
+
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(init, payer = authority, space = 8 + 32 + 8 + 1)]
-    pub mint: Account<'info, TokenMint>,
+    pub fn approve(ctx: Context<Approve>, amount: u64) -> Result<()> {
+        verify_authority(&ctx.accounts.owner, ctx.remaining_accounts)?;
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+        let token_account = &mut ctx.accounts.token_account;
 
-    pub system_program: Program<'info, System>,
-}
+        // Approving replaces any previous delegate and allowance.
+        token_account.delegate = Some(ctx.accounts.delegate.key());
+        token_account.delegated_amount = amount;
 
-#[derive(Accounts)]
-pub struct CreateTokenAccount<'info> {
-    #[account(mut)]
-    pub mint: Account<'info, TokenMint>,
+        msg!(
+            "Approved {} to spend {} tokens of {}",
+            ctx.accounts.delegate.key(),
+            amount,
+            token_account.owner
+        );
 
-    #[account(
-        init,
-        payer = payer,
-        space = 8 + 32 + 32 + 8,
-        seeds = [b"token", owner.key().as_ref(), mint.key().as_ref()],
-        bump
-    )]
-    pub token_account: Account<'info, TokenAccount>,
+        emit!(DelegateApproved {
+            token_account: token_account.key(),
+            delegate: ctx.accounts.delegate.key(),
+            amount,
+        });
 
-    /// CHECK: This is just used as a seed for the PDA
-    pub owner: AccountInfo<'info>,
+        Ok(())
+    }
 
-    #[account(mut)]
-    pub payer: Signer<'info>,
+    pub fn permit(
+        ctx: Context<Permit>,
+        spender: Pubkey,
+        amount: u64,
+        nonce: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp <= deadline,
+            ErrorCode::PermitExpired
+        );
 
-    pub system_program: Program<'info, System>,
-}
+        let token_account = &mut ctx.accounts.token_account;
 
-#[derive(Accounts)]
-pub struct MintTokens<'info> {
-    #[account(mut, has_one = authority)]
-    pub mint: Account<'info, TokenMint>,
+        let message = permit_message(
+            &token_account.mint,
+            &token_account.owner,
+            &spender,
+            amount,
+            nonce,
+            deadline,
+        );
+        verify_ed25519_signature(&ctx.accounts.instructions, &token_account.owner, &message)?;
 
-    #[account(mut, seeds = [b"token", token_account.owner.as_ref(), mint.key().as_ref()], bump)]
-    pub token_account: Account<'info, TokenAccount>,
+        // Creating the nonce account fails if this permit was already submitted.
+        let permit_nonce = &mut ctx.accounts.permit_nonce;
+        permit_nonce.owner = token_account.owner;
+        permit_nonce.mint = token_account.mint;
+        permit_nonce.nonce = nonce;
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
-}
+        // Like `approve`, a permit replaces any previous delegate and allowance.
+        token_account.delegate = Some(spender);
+        token_account.delegated_amount = amount;
 
-#[derive(Accounts)]
-pub struct Transfer<'info> {
-    #[account(mut, seeds = [b"token", owner.key().as_ref(), from.mint.as_ref()], bump, has_one = owner)]
-    pub from: Account<'info, TokenAccount>,
+        msg!(
+            "Permit approved {} to spend {} tokens of {}",
+            spender,
+            amount,
+            token_account.owner
+        );
 
-    #[account(mut)]
-    pub to: Account<'info, TokenAccount>,
+        emit!(DelegateApproved {
+            token_account: token_account.key(),
+            delegate: spender,
+            amount,
+        });
 
-    pub owner: Signer<'info>,
-}
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct Burn<'info> {
-    #[account(mut)]
-    pub mint: Account<'info, TokenMint>,
+    pub fn cancel_permit(ctx: Context<CancelPermit>, nonce: u64) -> Result<()> {
+        verify_authority(&ctx.accounts.owner, ctx.remaining_accounts)?;
 
-    #[account(mut, seeds = [b"token", owner.key().as_ref(), token_account.mint.as_ref()], bump, has_one = owner)]
-    pub token_account: Account<'info, TokenAccount>,
+        // Using up the nonce makes any permit signed with it fail, as if already submitted.
+        let permit_nonce = &mut ctx.accounts.permit_nonce;
+        permit_nonce.owner = ctx.accounts.owner.key();
+        permit_nonce.mint = ctx.accounts.mint.key();
+        permit_nonce.nonce = nonce;
 
-    pub owner: Signer<'info>,
-}
+        msg!("Cancelled permit nonce {} of {}", nonce, permit_nonce.owner);
 
-#[account]
-pub struct TokenMint {
-    pub authority: Pubkey,
-    pub total_supply: u64,
-    pub decimals: u8,
-}
+        emit!(PermitCancelled {
+            mint: permit_nonce.mint,
+            owner: permit_nonce.owner,
+            nonce,
+        });
 
-#[account]
-pub struct TokenAccount {
-    pub owner: Pubkey,
-    pub mint: Pubkey,
-    pub amount: u64,
-}
+        Ok(())
+    }
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Token account mint does not match the provided mint")]
-    MintMismatch,
-    #[msg("Arithmetic overflow")]
-    Overflow,
-    #[msg("Sender has insufficient funds")]
-    InsufficientFunds,
+    pub fn revoke(ctx: Context<Revoke>) -> Result<()> {
+        verify_authority(&ctx.accounts.owner, ctx.remaining_accounts)?;
+
+        let token_account = &mut ctx.accounts.token_account;
+
+        token_account.delegate = None;
+        token_account.delegated_amount = 0;
+
+        msg!("Revoked delegate of {}", token_account.owner);
+
+        emit!(DelegateRevoked {
+            token_account: token_account.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn pause(ctx: Context<SetPaused>) -> Result<()> {
+        let mint = &mut ctx.accounts.mint;
+
+        require!(!mint.paused, ErrorCode::MintPaused);
+
+        mint.paused = true;
+
+        msg!("Mint {} paused", mint.key());
+
+        emit!(Paused { mint: mint.key() });
+
+        Ok(())
+    }
+
+    pub fn unpause(ctx: Context<SetPaused>) -> Result<()> {
+        let mint = &mut ctx.accounts.mint;
+
+        require!(mint.paused, ErrorCode::MintNotPaused);
+
+        mint.paused = false;
+
+        msg!("Mint {} unpaused", mint.key());
+
+        emit!(Unpaused { mint: mint.key() });
+
+        Ok(())
+    }
+
+    pub fn initialize_metadata(
+        ctx: Context<InitializeMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        validate_metadata(&name, &symbol, &uri)?;
+
+        let metadata = &mut ctx.accounts.metadata;
+
+        metadata.mint = ctx.accounts.mint.key();
+        metadata.update_authority = Some(ctx.accounts.authority.key());
+        metadata.name = name;
+        metadata.symbol = symbol;
+        metadata.uri = uri;
+        metadata.additional_metadata = Vec::new();
+
+        msg!(
+            "Metadata initialized for mint {}: {} ({})",
+            metadata.mint,
+            metadata.name,
+            metadata.symbol
+        );
+
+        emit!(MetadataUpdated {
+            mint: metadata.mint,
+            name: metadata.name.clone(),
+            symbol: metadata.symbol.clone(),
+            uri: metadata.uri.clone(),
+        });
+
+        Ok(())
+    }
+
+    pub fn update_metadata(
+        ctx: Context<UpdateMetadata>,
+        name: Option<String>,
+        symbol: Option<String>,
+        uri: Option<String>,
+    ) -> Result<()> {
+        verify_authority(&ctx.accounts.update_authority, ctx.remaining_accounts)?;
+
+        let metadata = &mut ctx.accounts.metadata;
+
+        // Fields left as `None` keep their current value.
+        let name = name.unwrap_or_else(|| metadata.name.clone());
+        let symbol = symbol.unwrap_or_else(|| metadata.symbol.clone());
+        let uri = uri.unwrap_or_else(|| metadata.uri.clone());
+        validate_metadata(&name, &symbol, &uri)?;
+
+        metadata.name = name;
+        metadata.symbol = symbol;
+        metadata.uri = uri;
+
+        msg!("Metadata updated for mint {}", metadata.mint);
+
+        emit!(MetadataUpdated {
+            mint: metadata.mint,
+            name: metadata.name.clone(),
+            symbol: metadata.symbol.clone(),
+            uri: metadata.uri.clone(),
+        });
+
+        Ok(())
+    }
+
+    pub fn set_metadata_field(
+        ctx: Context<UpdateMetadata>,
+        key: String,
+        value: String,
+    ) -> Result<()> {
+        verify_authority(&ctx.accounts.update_authority, ctx.remaining_accounts)?;
+
+        require!(
+            key.len() <= MAX_METADATA_KEY_LEN && value.len() <= MAX_METADATA_VALUE_LEN,
+            ErrorCode::MetadataTooLong
+        );
+
+        let metadata = &mut ctx.accounts.metadata;
+
+        match metadata
+            .additional_metadata
+            .iter_mut()
+            .find(|field| field.key == key)
+        {
+            Some(field) => field.value = value.clone(),
+            None => {
+                require!(
+                    metadata.additional_metadata.len() < MAX_METADATA_FIELDS,
+                    ErrorCode::TooManyMetadataFields
+                );
+                metadata.additional_metadata.push(MetadataField {
+                    key: key.clone(),
+                    value: value.clone(),
+                });
+            }
+        }
+
+        msg!("Metadata field {} set for mint {}", key, metadata.mint);
+
+        emit!(MetadataFieldSet {
+            mint: metadata.mint,
+            key,
+            value,
+        });
+
+        Ok(())
+    }
+
+    pub fn remove_metadata_field(ctx: Context<UpdateMetadata>, key: String) -> Result<()> {
+        verify_authority(&ctx.accounts.update_authority, ctx.remaining_accounts)?;
+
+        let metadata = &mut ctx.accounts.metadata;
+
+        let index = metadata
+            .additional_metadata
+            .iter()
+            .position(|field| field.key == key)
+            .ok_or(ErrorCode::MetadataFieldNotFound)?;
+        metadata.additional_metadata.remove(index);
+
+        msg!("Metadata field {} removed for mint {}", key, metadata.mint);
+
+        emit!(MetadataFieldRemoved {
+            mint: metadata.mint,
+            key,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_metadata_update_authority(
+        ctx: Context<UpdateMetadata>,
+        new_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        verify_authority(&ctx.accounts.update_authority, ctx.remaining_accounts)?;
+
+        let metadata = &mut ctx.accounts.metadata;
+
+        // Setting `None` makes the metadata permanently immutable.
+        metadata.update_authority = new_authority;
+
+        msg!(
+            "Metadata update authority changed for mint {}",
+            metadata.mint
+        );
+
+        emit!(MetadataUpdateAuthorityChanged {
+            mint: metadata.mint,
+            new_authority,
+        });
+
+        Ok(())
+    }
+
+    pub fn freeze_account(ctx: Context<FreezeAccount>) -> Result<()> {
+        verify_authority(&ctx.accounts.freeze_authority, ctx.remaining_accounts)?;
+
+        let token_account = &mut ctx.accounts.token_account;
+
+        require!(
+            token_account.state != AccountState::Frozen,
+            ErrorCode::AccountFrozen
+        );
+
+        token_account.state = AccountState::Frozen;
+
+        msg!("Froze token account of {}", token_account.owner);
+
+        emit!(TokenAccountFrozen {
+            token_account: token_account.key(),
+            mint: token_account.mint,
+        });
+
+        Ok(())
+    }
+
+    pub fn thaw_account(ctx: Context<FreezeAccount>) -> Result<()> {
+        verify_authority(&ctx.accounts.freeze_authority, ctx.remaining_accounts)?;
+
+        let token_account = &mut ctx.accounts.token_account;
+
+        require!(
+            token_account.state == AccountState::Frozen,
+            ErrorCode::AccountNotFrozen
+        );
+
+        token_account.state = AccountState::Initialized;
+
+        msg!("Thawed token account of {}", token_account.owner);
+
+        emit!(TokenAccountThawed {
+            token_account: token_account.key(),
+            mint: token_account.mint,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_transfer_fee(
+        ctx: Context<SetTransferFee>,
+        basis_points: u16,
+        maximum_fee: u64,
+    ) -> Result<()> {
+        require!(
+            basis_points <= MAX_FEE_BASIS_POINTS,
+            ErrorCode::InvalidTransferFee
+        );
+
+        let mint = &mut ctx.accounts.mint;
+
+        mint.transfer_fee_basis_points = basis_points;
+        mint.maximum_fee = maximum_fee;
+
+        msg!(
+            "Transfer fee set to {} basis points, capped at {}",
+            basis_points,
+            maximum_fee
+        );
+
+        emit!(TransferFeeSet {
+            mint: mint.key(),
+            basis_points,
+            maximum_fee,
+        });
+
+        Ok(())
+    }
+
+    pub fn harvest_withheld(ctx: Context<HarvestWithheld>) -> Result<()> {
+        let mint = &mut ctx.accounts.mint;
+        let token_account = &mut ctx.accounts.token_account;
+
+        let amount = token_account.withheld_amount;
+        require!(amount > 0, ErrorCode::NoWithheldFees);
+
+        // Harvesting only moves fees onto the mint; frozen accounts are not exempt.
+        token_account.withheld_amount = 0;
+        mint.withheld_amount = mint
+            .withheld_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+
+        msg!(
+            "Harvested {} withheld tokens from {}",
+            amount,
+            token_account.owner
+        );
+
+        emit!(WithheldHarvested {
+            mint: mint.key(),
+            token_account: token_account.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    pub fn withdraw_withheld(ctx: Context<WithdrawWithheld>) -> Result<()> {
+        let mint = &mut ctx.accounts.mint;
+        let destination = &mut ctx.accounts.destination;
+
+        let amount = mint.withheld_amount;
+        require!(amount > 0, ErrorCode::NoWithheldFees);
+
+        require!(
+            destination.state != AccountState::Frozen,
+            ErrorCode::AccountFrozen
+        );
+
+        mint.withheld_amount = 0;
+        destination.amount = destination
+            .amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+
+        msg!(
+            "Withdrew {} withheld tokens to {}",
+            amount,
+            destination.owner
+        );
+
+        emit!(WithheldWithdrawn {
+            mint: mint.key(),
+            destination: destination.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    pub fn add_to_denylist(ctx: Context<AddToDenylist>) -> Result<()> {
+        verify_authority(&ctx.accounts.freeze_authority, ctx.remaining_accounts)?;
+
+        let deny_entry = &mut ctx.accounts.deny_entry;
+
+        // The entry's existence is what blocks the wallet; see `require_not_denied`.
+        deny_entry.mint = ctx.accounts.mint.key();
+        deny_entry.wallet = ctx.accounts.wallet.key();
+
+        msg!("Added {} to the denylist", deny_entry.wallet);
+
+        emit!(DenylistAdded {
+            mint: deny_entry.mint,
+            wallet: deny_entry.wallet,
+        });
+
+        Ok(())
+    }
+
+    pub fn remove_from_denylist(ctx: Context<RemoveFromDenylist>) -> Result<()> {
+        verify_authority(&ctx.accounts.freeze_authority, ctx.remaining_accounts)?;
+
+        let deny_entry = &ctx.accounts.deny_entry;
+
+        msg!("Removed {} from the denylist", deny_entry.wallet);
+
+        emit!(DenylistRemoved {
+            mint: deny_entry.mint,
+            wallet: deny_entry.wallet,
+        });
+
+        Ok(())
+    }
+
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        threshold: u8,
+        signers: Vec<Pubkey>,
+    ) -> Result<()> {
+        require!(
+            threshold > 0
+                && threshold as usize <= signers.len()
+                && signers.len() <= MAX_MULTISIG_SIGNERS,
+            ErrorCode::InvalidMultisig
+        );
+
+        // A repeated signer would count twice toward the threshold.
+        for (i, signer) in signers.iter().enumerate() {
+            require!(!signers[..i].contains(signer), ErrorCode::InvalidMultisig);
+        }
+
+        let multisig = &mut ctx.accounts.multisig;
+
+        multisig.threshold = threshold;
+        multisig.signers = signers;
+
+        msg!(
+            "Multisig created: {} of {} signers",
+            threshold,
+            multisig.signers.len()
+        );
+
+        emit!(MultisigCreated {
+            multisig: multisig.key(),
+            threshold,
+            signers: multisig.signers.clone(),
+        });
+
+        Ok(())
+    }
+
+    pub fn set_mint_delay(ctx: Context<SetMintAuthority>, delay: i64) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let mint = &mut ctx.accounts.mint;
+
+        require!(delay >= 0, ErrorCode::InvalidMintDelay);
+
+        // Shortening the delay takes effect only once the current delay has passed, so
+        // holders still see every mint queued before then coming.
+        if delay < mint.mint_delay {
+            let apply_after = Clock::get()?
+                .unix_timestamp
+                .checked_add(mint.mint_delay)
+                .ok_or(ErrorCode::Overflow)?;
+            mint.pending_mint_delay = Some(delay);
+            mint.mint_delay_change_after = apply_after;
+
+            msg!(
+                "Mint delay of {} seconds queued, applicable after {}",
+                delay,
+                apply_after
+            );
+
+            emit!(MintDelayChangeQueued {
+                mint: mint.key(),
+                delay,
+                apply_after,
+            });
+
+            return Ok(());
+        }
+
+        // Lengthening applies at once and replaces any shorter delay still pending.
+        mint.mint_delay = delay;
+        mint.pending_mint_delay = None;
+
+        msg!("Mint delay set to {} seconds", delay);
+
+        emit!(MintDelaySet {
+            mint: mint.key(),
+            delay,
+        });
+
+        Ok(())
+    }
+
+    pub fn apply_mint_delay(ctx: Context<SetMintAuthority>) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let mint = &mut ctx.accounts.mint;
+
+        let delay = mint
+            .pending_mint_delay
+            .ok_or(ErrorCode::MintDelayChangeNotReady)?;
+        require!(
+            Clock::get()?.unix_timestamp >= mint.mint_delay_change_after,
+            ErrorCode::MintDelayChangeNotReady
+        );
+
+        mint.mint_delay = delay;
+        mint.pending_mint_delay = None;
+
+        msg!("Mint delay set to {} seconds", delay);
+
+        emit!(MintDelaySet {
+            mint: mint.key(),
+            delay,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_mint_rate_limit(
+        ctx: Context<SetMintAuthority>,
+        limit: u64,
+        window: i64,
+    ) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let mint = &mut ctx.accounts.mint;
+
+        // Loosening the limit would let a compromised authority lift it before minting.
+        require!(window > 0, ErrorCode::InvalidMintRateLimit);
+        if let Some(current_limit) = mint.mint_rate_limit {
+            require!(
+                limit <= current_limit && window >= mint.mint_rate_window,
+                ErrorCode::InvalidMintRateLimit
+            );
+        }
+
+        // Settle the capacity in use at the old rate before it changes.
+        let now = Clock::get()?.unix_timestamp;
+        mint.rate_limit_used = mint.mint_capacity_used(now);
+        mint.rate_limit_updated_at = now;
+
+        mint.mint_rate_limit = Some(limit);
+        mint.mint_rate_window = window;
+
+        msg!(
+            "Mint rate limit set to {} tokens per {} seconds",
+            limit,
+            window
+        );
+
+        emit!(MintRateLimitSet {
+            mint: mint.key(),
+            limit,
+            window,
+        });
+
+        Ok(())
+    }
+
+    pub fn queue_mint(ctx: Context<QueueMint>, amount: u64) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let mint = &mut ctx.accounts.mint;
+        let pending_mint = &mut ctx.accounts.pending_mint;
+
+        let execute_after = Clock::get()?
+            .unix_timestamp
+            .checked_add(mint.mint_delay)
+            .ok_or(ErrorCode::Overflow)?;
+
+        pending_mint.mint = mint.key();
+        pending_mint.id = mint.queued_mint_count;
+        pending_mint.token_account = ctx.accounts.token_account.key();
+        pending_mint.amount = amount;
+        pending_mint.execute_after = execute_after;
+
+        mint.queued_mint_count = mint
+            .queued_mint_count
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        msg!(
+            "Queued mint of {} tokens to {}, executable after {}",
+            amount,
+            ctx.accounts.token_account.owner,
+            execute_after
+        );
+
+        emit!(MintQueued {
+            mint: mint.key(),
+            pending_mint: pending_mint.key(),
+            token_account: pending_mint.token_account,
+            amount,
+            execute_after,
+        });
+
+        Ok(())
+    }
+
+    pub fn execute_mint(ctx: Context<ExecuteMint>) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let mint = &mut ctx.accounts.mint;
+        let pending_mint = &ctx.accounts.pending_mint;
+        let token_account = &mut ctx.accounts.token_account;
+
+        require!(
+            Clock::get()?.unix_timestamp >= pending_mint.execute_after,
+            ErrorCode::MintNotReady
+        );
+
+        require!(!mint.paused, ErrorCode::MintPaused);
+
+        require!(
+            token_account.state != AccountState::Frozen,
+            ErrorCode::AccountFrozen
+        );
+
+        require_not_denied(&ctx.accounts.deny_entry)?;
+
+        consume_mint_capacity(mint, pending_mint.amount)?;
+
+        mint.total_supply = mint
+            .total_supply
+            .checked_add(pending_mint.amount)
+            .ok_or(ErrorCode::Overflow)?;
+
+        if let Some(max_supply) = mint.max_supply {
+            require!(
+                mint.total_supply <= max_supply,
+                ErrorCode::MaxSupplyExceeded
+            );
+        }
+
+        token_account.amount = token_account
+            .amount
+            .checked_add(pending_mint.amount)
+            .ok_or(ErrorCode::Overflow)?;
+
+        msg!(
+            "Executed queued mint of {} tokens to {}",
+            pending_mint.amount,
+            token_account.owner
+        );
+
+        emit!(TokensMinted {
+            mint: mint.key(),
+            token_account: token_account.key(),
+            amount: pending_mint.amount,
+            total_supply: mint.total_supply,
+        });
+
+        emit!(QueuedMintExecuted {
+            mint: mint.key(),
+            pending_mint: pending_mint.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_mint(ctx: Context<CancelMint>) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let pending_mint = &ctx.accounts.pending_mint;
+
+        msg!("Cancelled queued mint of {} tokens", pending_mint.amount);
+
+        emit!(QueuedMintCancelled {
+            mint: pending_mint.mint,
+            pending_mint: pending_mint.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn add_minter(ctx: Context<AddMinter>, allowance: u64) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let minter_role = &mut ctx.accounts.minter_role;
+
+        minter_role.mint = ctx.accounts.mint.key();
+        minter_role.minter = ctx.accounts.minter.key();
+        minter_role.allowance = allowance;
+
+        msg!(
+            "Added minter {} with an allowance of {}",
+            minter_role.minter,
+            allowance
+        );
+
+        emit!(MinterAllowanceSet {
+            mint: minter_role.mint,
+            minter: minter_role.minter,
+            allowance,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_minter_allowance(ctx: Context<SetMinterAllowance>, allowance: u64) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let minter_role = &mut ctx.accounts.minter_role;
+
+        // The new allowance replaces whatever is left of the old one.
+        minter_role.allowance = allowance;
+
+        msg!(
+            "Set allowance of minter {} to {}",
+            minter_role.minter,
+            allowance
+        );
+
+        emit!(MinterAllowanceSet {
+            mint: minter_role.mint,
+            minter: minter_role.minter,
+            allowance,
+        });
+
+        Ok(())
+    }
+
+    pub fn remove_minter(ctx: Context<RemoveMinter>) -> Result<()> {
+        verify_authority(&ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let minter_role = &ctx.accounts.minter_role;
+
+        msg!("Removed minter {}", minter_role.minter);
+
+        emit!(MinterRemoved {
+            mint: minter_role.mint,
+            minter: minter_role.minter,
+        });
+
+        Ok(())
+    }
+
+    pub fn create_vesting(
+        ctx: Context<CreateVesting>,
+        amount: u64,
+        start_time: i64,
+        cliff_time: i64,
+        end_time: i64,
+        revocable: bool,
+    ) -> Result<()> {
+        verify_authority(&ctx.accounts.grantor, ctx.remaining_accounts)?;
+
+        require!(
+            amount > 0
+                && start_time <= cliff_time
+                && cliff_time <= end_time
+                && start_time < end_time,
+            ErrorCode::InvalidVestingSchedule
+        );
+
+        let mint = &mut ctx.accounts.mint;
+        let source = &mut ctx.accounts.source;
+        let vesting = &mut ctx.accounts.vesting;
+
+        require!(!mint.paused, ErrorCode::MintPaused);
+        require!(!mint.non_transferable, ErrorCode::NonTransferable);
+        require!(
+            source.state != AccountState::Frozen,
+            ErrorCode::AccountFrozen
+        );
+        require_not_denied(&ctx.accounts.deny_entry)?;
+        require!(source.amount >= amount, ErrorCode::InsufficientFunds);
+
+        // The tokens leave the grantor's account and are held by the vesting account.
+        source.amount = source
+            .amount
+            .checked_sub(amount)
+            .ok_or(ErrorCode::Overflow)?;
+
+        vesting.mint = mint.key();
+        vesting.id = mint.vesting_count;
+        vesting.grantor = source.owner;
+        vesting.beneficiary = ctx.accounts.beneficiary.key();
+        vesting.total_amount = amount;
+        vesting.claimed_amount = 0;
+        vesting.start_time = start_time;
+        vesting.cliff_time = cliff_time;
+        vesting.end_time = end_time;
+        vesting.revocable = revocable;
+
+        mint.vesting_count = mint
+            .vesting_count
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        msg!(
+            "Vesting {} tokens from {} to {}, cliff at {}, fully vested at {}",
+            amount,
+            vesting.grantor,
+            vesting.beneficiary,
+            cliff_time,
+            end_time
+        );
+
+        emit!(VestingCreated {
+            mint: vesting.mint,
+            vesting: vesting.key(),
+            grantor: vesting.grantor,
+            beneficiary: vesting.beneficiary,
+            amount,
+            start_time,
+            cliff_time,
+            end_time,
+            revocable,
+        });
+
+        Ok(())
+    }
+
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        verify_authority(&ctx.accounts.beneficiary, ctx.remaining_accounts)?;
+
+        let vesting = &mut ctx.accounts.vesting;
+        let token_account = &mut ctx.accounts.token_account;
+
+        require!(!ctx.accounts.mint.paused, ErrorCode::MintPaused);
+        require!(
+            token_account.state != AccountState::Frozen,
+            ErrorCode::AccountFrozen
+        );
+        require_not_denied(&ctx.accounts.deny_entry)?;
+
+        let vested = vesting.vested_amount(Clock::get()?.unix_timestamp);
+        let amount = vested.saturating_sub(vesting.claimed_amount);
+        require!(amount > 0, ErrorCode::NothingToClaim);
+
+        // The claim is charged the mint's current transfer fee, as a transfer would be.
+        let fee = calculate_transfer_fee(&ctx.accounts.mint, amount);
+
+        vesting.claimed_amount = vested;
+        credit_with_fee(token_account, amount, fee)?;
+
+        msg!(
+            "Claimed {} vested tokens (fee {}), {} of {} claimed so far",
+            amount,
+            fee,
+            vesting.claimed_amount,
+            vesting.total_amount
+        );
+
+        emit!(VestedClaimed {
+            mint: vesting.mint,
+            vesting: vesting.key(),
+            beneficiary: vesting.beneficiary,
+            amount,
+            fee,
+        });
+
+        // A schedule with nothing left to claim is closed, returning its rent to the grantor.
+        if vesting.claimed_amount == vesting.total_amount {
+            vesting.close(ctx.accounts.grantor.to_account_info())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn revoke_vesting(ctx: Context<RevokeVesting>) -> Result<()> {
+        verify_authority(&ctx.accounts.grantor, ctx.remaining_accounts)?;
+
+        let vesting = &mut ctx.accounts.vesting;
+        let token_account = &mut ctx.accounts.token_account;
+
+        require!(vesting.revocable, ErrorCode::VestingNotRevocable);
+        require!(!ctx.accounts.mint.paused, ErrorCode::MintPaused);
+        require!(
+            token_account.state != AccountState::Frozen,
+            ErrorCode::AccountFrozen
+        );
+        require_not_denied(&ctx.accounts.deny_entry)?;
+
+        // End the schedule now: what has vested stays claimable by the beneficiary,
+        // the rest returns to the grantor.
+        let now = Clock::get()?.unix_timestamp;
+        let vested = vesting.vested_amount(now);
+        let unvested = vesting.total_amount - vested;
+
+        vesting.total_amount = vested;
+        vesting.start_time = vesting.start_time.min(now);
+        vesting.cliff_time = vesting.cliff_time.min(now);
+        vesting.end_time = now;
+        vesting.revocable = false;
+
+        token_account.amount = token_account
+            .amount
+            .checked_add(unvested)
+            .ok_or(ErrorCode::Overflow)?;
+
+        msg!(
+            "Revoked vesting of {}, returning {} unvested tokens",
+            vesting.beneficiary,
+            unvested
+        );
+
+        emit!(VestingRevoked {
+            mint: vesting.mint,
+            vesting: vesting.key(),
+            grantor: vesting.grantor,
+            returned_amount: unvested,
+        });
+
+        // A schedule with nothing left to claim is closed, returning its rent to the grantor.
+        if vesting.claimed_amount == vesting.total_amount {
+            vesting.close(ctx.accounts.grantor.to_account_info())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn make_offer(
+        ctx: Context<MakeOffer>,
+        id: u64,
+        offer_amount: u64,
+        request_amount: u64,
+    ) -> Result<()> {
+        verify_authority(&ctx.accounts.maker, ctx.remaining_accounts)?;
+
+        let offer_mint = &ctx.accounts.offer_mint;
+        let request_mint = &ctx.accounts.request_mint;
+        let source = &mut ctx.accounts.source;
+        let escrow = &mut ctx.accounts.escrow;
+        let offer = &mut ctx.accounts.offer;
+
+        require!(
+            offer_amount > 0
+                && request_amount > 0
+                && offer_mint.key() != request_mint.key()
+                && !request_mint.non_transferable,
+            ErrorCode::InvalidOffer
+        );
+        require!(!offer_mint.paused, ErrorCode::MintPaused);
+        require!(!offer_mint.non_transferable, ErrorCode::NonTransferable);
+        require!(
+            source.state != AccountState::Frozen,
+            ErrorCode::AccountFrozen
+        );
+        require_not_denied(&ctx.accounts.deny_entry)?;
+        require!(source.amount >= offer_amount, ErrorCode::InsufficientFunds);
+
+        offer.maker = ctx.accounts.maker.key();
+        offer.id = id;
+        offer.offer_mint = offer_mint.key();
+        offer.offer_amount = offer_amount;
+        offer.request_mint = request_mint.key();
+        offer.request_amount = request_amount;
+
+        // The escrow is a token account owned by the offer, so only this program can
+        // move its tokens, and only through `take_offer` or `cancel_offer`.
+        escrow.owner = offer.key();
+        escrow.mint = offer_mint.key();
+        escrow.amount = offer_amount;
+        escrow.state = AccountState::Initialized;
+        escrow.delegate = None;
+        escrow.delegated_amount = 0;
+
+        source.amount = source
+            .amount
+            .checked_sub(offer_amount)
+            .ok_or(ErrorCode::Overflow)?;
+
+        msg!(
+            "Offered {} tokens of {} for {} tokens of {}",
+            offer_amount,
+            offer.offer_mint,
+            request_amount,
+            offer.request_mint
+        );
+
+        emit!(OfferMade {
+            offer: offer.key(),
+            maker: offer.maker,
+            offer_mint: offer.offer_mint,
+            offer_amount,
+            request_mint: offer.request_mint,
+            request_amount,
+        });
+
+        Ok(())
+    }
+
+    pub fn take_offer(ctx: Context<TakeOffer>) -> Result<()> {
+        verify_authority(&ctx.accounts.taker, ctx.remaining_accounts)?;
+
+        let accounts = &mut *ctx.accounts;
+        let offer = &accounts.offer;
+
+        require!(
+            !accounts.offer_mint.paused && !accounts.request_mint.paused,
+            ErrorCode::MintPaused
+        );
+        require!(
+            [
+                &accounts.escrow,
+                &accounts.maker_request_account,
+                &accounts.taker_offer_account,
+                &accounts.taker_request_account,
+            ]
+            .iter()
+            .all(|account| account.state != AccountState::Frozen),
+            ErrorCode::AccountFrozen
+        );
+        for deny_entry in [
+            &accounts.maker_offer_deny_entry,
+            &accounts.maker_request_deny_entry,
+            &accounts.taker_offer_deny_entry,
+            &accounts.taker_request_deny_entry,
+        ] {
+            require_not_denied(deny_entry)?;
+        }
+        require!(
+            accounts.taker_request_account.amount >= offer.request_amount
+                && accounts.escrow.amount >= offer.offer_amount,
+            ErrorCode::InsufficientFunds
+        );
+
+        // Each leg is charged its mint's transfer fee, as a transfer would be.
+        let request_fee = calculate_transfer_fee(&accounts.request_mint, offer.request_amount);
+        let offer_fee = calculate_transfer_fee(&accounts.offer_mint, offer.offer_amount);
+
+        accounts.taker_request_account.amount = accounts
+            .taker_request_account
+            .amount
+            .checked_sub(offer.request_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        credit_with_fee(
+            &mut accounts.maker_request_account,
+            offer.request_amount,
+            request_fee,
+        )?;
+
+        accounts.escrow.amount = accounts
+            .escrow
+            .amount
+            .checked_sub(offer.offer_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        credit_with_fee(
+            &mut accounts.taker_offer_account,
+            offer.offer_amount,
+            offer_fee,
+        )?;
+
+        // Tokens sent to the escrow by anyone else go back to the maker, who gets the
+        // escrow's rent as well once it is closed.
+        refund_escrow(&mut accounts.escrow, &mut accounts.maker_offer_account)?;
+
+        msg!(
+            "Offer taken by {}: {} tokens for {} tokens",
+            accounts.taker.key(),
+            offer.offer_amount,
+            offer.request_amount
+        );
+
+        emit!(OfferTaken {
+            offer: offer.key(),
+            maker: offer.maker,
+            taker: accounts.taker.key(),
+            offer_fee,
+            request_fee,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_offer(ctx: Context<CancelOffer>) -> Result<()> {
+        verify_authority(&ctx.accounts.maker, ctx.remaining_accounts)?;
+
+        let escrow = &mut ctx.accounts.escrow;
+        let token_account = &mut ctx.accounts.token_account;
+
+        require!(!ctx.accounts.offer_mint.paused, ErrorCode::MintPaused);
+        require!(
+            escrow.state != AccountState::Frozen && token_account.state != AccountState::Frozen,
+            ErrorCode::AccountFrozen
+        );
+        require_not_denied(&ctx.accounts.deny_entry)?;
+
+        let amount = escrow.amount;
+        refund_escrow(escrow, token_account)?;
+
+        msg!("Offer cancelled, returning {} tokens", amount);
+
+        emit!(OfferCancelled {
+            offer: ctx.accounts.offer.key(),
+            maker: ctx.accounts.offer.maker,
+        });
+
+        Ok(())
+    }
+
+    pub fn create_distributor(
+        ctx: Context<CreateDistributor>,
+        merkle_root: [u8; 32],
+        num_claims: u64,
+        total_amount: u64,
+        end_time: i64,
+    ) -> Result<()> {
+        verify_authority(&ctx.accounts.funder, ctx.remaining_accounts)?;
+
+        require!(
+            num_claims > 0 && total_amount > 0 && end_time > Clock::get()?.unix_timestamp,
+            ErrorCode::InvalidDistributor
+        );
+
+        let mint = &ctx.accounts.mint;
+        let source = &mut ctx.accounts.source;
+        let vault = &mut ctx.accounts.vault;
+        let distributor = &mut ctx.accounts.distributor;
+
+        require!(!mint.paused, ErrorCode::MintPaused);
+        require!(!mint.non_transferable, ErrorCode::NonTransferable);
+        require!(
+            source.state != AccountState::Frozen,
+            ErrorCode::AccountFrozen
+        );
+        require_not_denied(&ctx.accounts.deny_entry)?;
+        require!(source.amount >= total_amount, ErrorCode::InsufficientFunds);
+
+        distributor.mint = mint.key();
+        distributor.merkle_root = merkle_root;
+        distributor.funder = ctx.accounts.funder.key();
+        distributor.num_claims = num_claims;
+        distributor.total_amount = total_amount;
+        distributor.claimed_amount = 0;
+        distributor.end_time = end_time;
+        distributor.open_bitmaps = 0;
+
+        // Like an offer's escrow, the vault is owned by the distributor so only
+        // `claim` and `close_distributor` can move its tokens.
+        vault.owner = distributor.key();
+        vault.mint = mint.key();
+        vault.amount = total_amount;
+        vault.state = AccountState::Initialized;
+        vault.delegate = None;
+        vault.delegated_amount = 0;
+
+        source.amount = source
+            .amount
+            .checked_sub(total_amount)
+            .ok_or(ErrorCode::Overflow)?;
+
+        msg!(
+            "Distributor created for {} claims of {} tokens in total, ending at {}",
+            num_claims,
+            total_amount,
+            end_time
+        );
+
+        emit!(DistributorCreated {
+            mint: distributor.mint,
+            distributor: distributor.key(),
+            merkle_root,
+            num_claims,
+            total_amount,
+            end_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn create_claim_bitmap(ctx: Context<CreateClaimBitmap>, chunk: u64) -> Result<()> {
+        let distributor = &mut ctx.accounts.distributor;
+        let claim_bitmap = &mut ctx.accounts.claim_bitmap;
+
+        require!(
+            chunk < distributor.num_claims.div_ceil(CLAIMS_PER_BITMAP),
+            ErrorCode::ClaimIndexOutOfRange
+        );
+
+        claim_bitmap.distributor = distributor.key();
+        claim_bitmap.chunk = chunk;
+        claim_bitmap.bits = vec![0; CLAIM_BITMAP_BYTES];
+
+        distributor.open_bitmaps = distributor
+            .open_bitmaps
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        msg!(
+            "Claim bitmap {} created for claims {} to {}",
+            chunk,
+            chunk * CLAIMS_PER_BITMAP,
+            (chunk + 1) * CLAIMS_PER_BITMAP - 1
+        );
+
+        Ok(())
+    }
+
+    pub fn claim(ctx: Context<Claim>, index: u64, amount: u64, proof: Vec<[u8; 32]>) -> Result<()> {
+        let distributor = &mut ctx.accounts.distributor;
+        let vault = &mut ctx.accounts.vault;
+        let claim_bitmap = &mut ctx.accounts.claim_bitmap;
+        let token_account = &mut ctx.accounts.token_account;
+
+        require!(
+            Clock::get()?.unix_timestamp < distributor.end_time,
+            ErrorCode::AirdropEnded
+        );
+        require!(
+            index < distributor.num_claims,
+            ErrorCode::ClaimIndexOutOfRange
+        );
+
+        let leaf = airdrop_leaf(index, &token_account.owner, amount);
+        require!(
+            verify_merkle_proof(&proof, &distributor.merkle_root, leaf),
+            ErrorCode::InvalidMerkleProof
+        );
+
+        let bit = (index % CLAIMS_PER_BITMAP) as usize;
+        let (byte, mask) = (bit / 8, 1u8 << (bit % 8));
+        require!(
+            claim_bitmap.bits[byte] & mask == 0,
+            ErrorCode::AlreadyClaimed
+        );
+        claim_bitmap.bits[byte] |= mask;
+
+        require!(!ctx.accounts.mint.paused, ErrorCode::MintPaused);
+        require!(
+            vault.state != AccountState::Frozen && token_account.state != AccountState::Frozen,
+            ErrorCode::AccountFrozen
+        );
+        require_not_denied(&ctx.accounts.deny_entry)?;
+        require!(vault.amount >= amount, ErrorCode::InsufficientFunds);
+
+        // The claim is charged the transfer fee, as a transfer out of the vault would be.
+        let fee = calculate_transfer_fee(&ctx.accounts.mint, amount);
+
+        vault.amount -= amount;
+        credit_with_fee(token_account, amount, fee)?;
+        distributor.claimed_amount = distributor
+            .claimed_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+
+        msg!(
+            "Claimed {} airdropped tokens (fee {}) for {} (claim #{})",
+            amount,
+            fee,
+            token_account.owner,
+            index
+        );
+
+        emit!(AirdropClaimed {
+            mint: distributor.mint,
+            distributor: distributor.key(),
+            claimant: token_account.owner,
+            index,
+            amount,
+            fee,
+        });
+
+        Ok(())
+    }
+
+    pub fn close_claim_bitmap(ctx: Context<CloseClaimBitmap>, chunk: u64) -> Result<()> {
+        verify_authority(&ctx.accounts.funder, ctx.remaining_accounts)?;
+
+        let distributor = &mut ctx.accounts.distributor;
+
+        require!(
+            Clock::get()?.unix_timestamp >= distributor.end_time,
+            ErrorCode::AirdropNotEnded
+        );
+
+        distributor.open_bitmaps -= 1;
+
+        msg!("Claim bitmap {} closed", chunk);
+
+        Ok(())
+    }
+
+    pub fn close_distributor(ctx: Context<CloseDistributor>) -> Result<()> {
+        verify_authority(&ctx.accounts.funder, ctx.remaining_accounts)?;
+
+        let distributor = &ctx.accounts.distributor;
+        let vault = &mut ctx.accounts.vault;
+        let token_account = &mut ctx.accounts.token_account;
+
+        require!(
+            Clock::get()?.unix_timestamp >= distributor.end_time,
+            ErrorCode::AirdropNotEnded
+        );
+        // Closing a bitmap needs its distributor, so they must all be closed first.
+        require!(distributor.open_bitmaps == 0, ErrorCode::ClaimBitmapsOpen);
+        require!(!ctx.accounts.mint.paused, ErrorCode::MintPaused);
+        require!(
+            vault.state != AccountState::Frozen && token_account.state != AccountState::Frozen,
+            ErrorCode::AccountFrozen
+        );
+        require_not_denied(&ctx.accounts.deny_entry)?;
+
+        // Unclaimed tokens go back to the funder, along with anything else sent to the vault.
+        let amount = vault.amount;
+        refund_escrow(vault, token_account)?;
+
+        msg!("Distributor closed, returning {} unclaimed tokens", amount);
+
+        emit!(DistributorClosed {
+            mint: distributor.mint,
+            distributor: distributor.key(),
+            funder: distributor.funder,
+            returned_amount: amount,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = authority, space = 8 + 33 + 8 + 1 + 33 + 33 + 9 + 33 + 1 + 33 + 2 + 8 + 8 + 1 + 33 + 1 + 8 + 8 + 9 + 8 + 8 + 8 + 8 + 9 + 8)]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintAuthority<'info> {
+    #[account(
+        mut,
+        constraint = mint.authority.is_some() @ ErrorCode::MintAuthorityRevoked,
+        constraint = mint.authority == Some(authority.key()) @ ErrorCode::InvalidMintAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    /// CHECK: The mint authority or a multisig, checked by `verify_authority`
+    pub authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptMintAuthority<'info> {
+    #[account(
+        mut,
+        constraint = mint.pending_authority.is_some() @ ErrorCode::NoPendingAuthority,
+        constraint = mint.pending_authority == Some(pending_authority.key()) @ ErrorCode::InvalidPendingAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    /// CHECK: The proposed mint authority or a multisig, checked by `verify_authority`
+    pub pending_authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateTokenAccount<'info> {
+    #[account(mut)]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 32 + 8 + 1 + 33 + 8 + 8,
+        seeds = [b"token", owner.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: This is just used as a seed for the PDA
+    pub owner: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseTokenAccount<'info> {
+    #[account(mut)]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(
+        mut,
+        seeds = [b"token", owner.key().as_ref(), mint.key().as_ref()],
+        bump,
+        has_one = owner,
+        close = destination
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Only receives the reclaimed rent lamports
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+
+    /// CHECK: The token account owner or a multisig, checked by `verify_authority`
+    pub owner: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MintTokens<'info> {
+    // Minters mint against their allowance without holding the mint authority, but
+    // like the authority they lose the ability once it is revoked.
+    #[account(
+        mut,
+        constraint = mint.authority.is_some() @ ErrorCode::MintAuthorityRevoked,
+        constraint = minter_role.is_some() || mint.authority == Some(authority.key()) @ ErrorCode::InvalidMintAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(mut, seeds = [b"token", token_account.owner.as_ref(), mint.key().as_ref()], bump)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: The mint authority or a minter, possibly a multisig, checked by `verify_authority`
+    pub authority: AccountInfo<'info>,
+
+    /// CHECK: Denylist entry of the recipient, only checked for existence
+    #[account(seeds = [b"deny", mint.key().as_ref(), token_account.owner.as_ref()], bump)]
+    pub deny_entry: AccountInfo<'info>,
+
+    /// Role of `authority` when it mints as a minter rather than the mint authority.
+    #[account(mut, seeds = [b"minter", mint.key().as_ref(), authority.key().as_ref()], bump)]
+    pub minter_role: Option<Account<'info, MinterRole>>,
+}
+
+#[derive(Accounts)]
+pub struct MintToMany<'info> {
+    #[account(
+        mut,
+        constraint = mint.authority.is_some() @ ErrorCode::MintAuthorityRevoked,
+        constraint = mint.authority == Some(authority.key()) @ ErrorCode::InvalidMintAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    /// CHECK: The mint authority or a multisig, checked by `verify_authority`
+    pub authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Transfer<'info> {
+    #[account(constraint = mint.key() == from.mint @ ErrorCode::MintMismatch)]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(mut, seeds = [b"token", from.owner.as_ref(), from.mint.as_ref()], bump)]
+    pub from: Account<'info, TokenAccount>,
+
+    // Sending to the same account would let the credit overwrite the debit on exit.
+    #[account(
+        mut,
+        seeds = [b"token", to.owner.as_ref(), to.mint.as_ref()],
+        bump,
+        constraint = to.key() != from.key() @ ErrorCode::SelfTransfer
+    )]
+    pub to: Account<'info, TokenAccount>,
+
+    /// CHECK: The owner of `from`, its approved delegate or the mint's permanent delegate;
+    /// may be a multisig, checked by `verify_authority`
+    pub authority: AccountInfo<'info>,
+
+    /// CHECK: Denylist entry of the sender, only checked for existence
+    #[account(seeds = [b"deny", mint.key().as_ref(), from.owner.as_ref()], bump)]
+    pub from_deny_entry: AccountInfo<'info>,
+
+    /// CHECK: Denylist entry of the recipient, only checked for existence
+    #[account(seeds = [b"deny", mint.key().as_ref(), to.owner.as_ref()], bump)]
+    pub to_deny_entry: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferMany<'info> {
+    #[account(constraint = mint.key() == from.mint @ ErrorCode::MintMismatch)]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(mut, seeds = [b"token", from.owner.as_ref(), from.mint.as_ref()], bump)]
+    pub from: Account<'info, TokenAccount>,
+
+    /// CHECK: The owner of `from`, its approved delegate or the mint's permanent delegate;
+    /// may be a multisig, checked by `verify_authority`
+    pub authority: AccountInfo<'info>,
+
+    /// CHECK: Denylist entry of the sender, only checked for existence
+    #[account(seeds = [b"deny", mint.key().as_ref(), from.owner.as_ref()], bump)]
+    pub from_deny_entry: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Burn<'info> {
+    #[account(mut)]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(mut, seeds = [b"token", token_account.owner.as_ref(), token_account.mint.as_ref()], bump)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: The owner of `token_account`, its approved delegate or the mint's permanent delegate;
+    /// may be a multisig, checked by `verify_authority`
+    pub authority: AccountInfo<'info>,
+
+    /// CHECK: Denylist entry of the token account owner, only checked for existence
+    #[account(seeds = [b"deny", mint.key().as_ref(), token_account.owner.as_ref()], bump)]
+    pub deny_entry: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Approve<'info> {
+    #[account(mut, seeds = [b"token", owner.key().as_ref(), token_account.mint.as_ref()], bump, has_one = owner)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Only recorded as the delegate, never read or written
+    pub delegate: AccountInfo<'info>,
+
+    /// CHECK: The token account owner or a multisig, checked by `verify_authority`
+    pub owner: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(spender: Pubkey, amount: u64, nonce: u64)]
+pub struct Permit<'info> {
+    #[account(mut, seeds = [b"token", token_account.owner.as_ref(), token_account.mint.as_ref()], bump)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 32 + 8,
+        seeds = [b"permit", token_account.owner.as_ref(), token_account.mint.as_ref(), &nonce.to_le_bytes()],
+        bump
+    )]
+    pub permit_nonce: Account<'info, PermitNonce>,
+
+    /// CHECK: The instructions sysvar, read to find the owner's Ed25519 signature
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    /// Submits the permit and pays for the nonce account; need not be the owner.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct CancelPermit<'info> {
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 32 + 8,
+        seeds = [b"permit", owner.key().as_ref(), mint.key().as_ref(), &nonce.to_le_bytes()],
+        bump
+    )]
+    pub permit_nonce: Account<'info, PermitNonce>,
+
+    /// CHECK: The owner who signed the permit, or a multisig; checked by `verify_authority`
+    pub owner: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Revoke<'info> {
+    #[account(mut, seeds = [b"token", owner.key().as_ref(), token_account.mint.as_ref()], bump, has_one = owner)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: The token account owner or a multisig, checked by `verify_authority`
+    pub owner: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeAccount<'info> {
+    #[account(
+        constraint = mint.freeze_authority.is_some() @ ErrorCode::NoFreezeAuthority,
+        constraint = mint.freeze_authority == Some(freeze_authority.key()) @ ErrorCode::InvalidFreezeAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(mut, seeds = [b"token", token_account.owner.as_ref(), mint.key().as_ref()], bump)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: The freeze authority or a multisig, checked by `verify_authority`
+    pub freeze_authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        constraint = mint.pause_authority.is_some() @ ErrorCode::NoPauseAuthority,
+        constraint = mint.pause_authority == Some(pause_authority.key()) @ ErrorCode::InvalidPauseAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    pub pause_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeMetadata<'info> {
+    #[account(
+        constraint = mint.authority.is_some() @ ErrorCode::MintAuthorityRevoked,
+        constraint = mint.authority == Some(authority.key()) @ ErrorCode::InvalidMintAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + TokenMetadata::INIT_SPACE,
+        seeds = [b"metadata", mint.key().as_ref()],
+        bump
+    )]
+    pub metadata: Account<'info, TokenMetadata>,
+
+    /// CHECK: The mint authority or a multisig, checked by `verify_authority`
+    pub authority: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMetadata<'info> {
+    #[account(
+        mut,
+        seeds = [b"metadata", metadata.mint.as_ref()],
+        bump,
+        constraint = metadata.update_authority.is_some() @ ErrorCode::MetadataImmutable,
+        constraint = metadata.update_authority == Some(update_authority.key()) @ ErrorCode::InvalidUpdateAuthority
+    )]
+    pub metadata: Account<'info, TokenMetadata>,
+
+    /// CHECK: The metadata update authority or a multisig, checked by `verify_authority`
+    pub update_authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetTransferFee<'info> {
+    #[account(
+        mut,
+        constraint = mint.fee_authority.is_some() @ ErrorCode::NoFeeAuthority,
+        constraint = mint.fee_authority == Some(fee_authority.key()) @ ErrorCode::InvalidFeeAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    pub fee_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct HarvestWithheld<'info> {
+    #[account(
+        mut,
+        constraint = mint.fee_authority.is_some() @ ErrorCode::NoFeeAuthority,
+        constraint = mint.fee_authority == Some(fee_authority.key()) @ ErrorCode::InvalidFeeAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(mut, seeds = [b"token", token_account.owner.as_ref(), mint.key().as_ref()], bump)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    pub fee_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawWithheld<'info> {
+    #[account(
+        mut,
+        constraint = mint.fee_authority.is_some() @ ErrorCode::NoFeeAuthority,
+        constraint = mint.fee_authority == Some(fee_authority.key()) @ ErrorCode::InvalidFeeAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(mut, seeds = [b"token", destination.owner.as_ref(), mint.key().as_ref()], bump)]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub fee_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddToDenylist<'info> {
+    #[account(
+        constraint = mint.freeze_authority.is_some() @ ErrorCode::NoFreezeAuthority,
+        constraint = mint.freeze_authority == Some(freeze_authority.key()) @ ErrorCode::InvalidFreezeAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 32,
+        seeds = [b"deny", mint.key().as_ref(), wallet.key().as_ref()],
+        bump
+    )]
+    pub deny_entry: Account<'info, DenylistEntry>,
+
+    /// CHECK: This is just used as a seed for the PDA
+    pub wallet: AccountInfo<'info>,
+
+    /// CHECK: The freeze authority or a multisig, checked by `verify_authority`
+    pub freeze_authority: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFromDenylist<'info> {
+    #[account(
+        constraint = mint.freeze_authority.is_some() @ ErrorCode::NoFreezeAuthority,
+        constraint = mint.freeze_authority == Some(freeze_authority.key()) @ ErrorCode::InvalidFreezeAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(
+        mut,
+        seeds = [b"deny", mint.key().as_ref(), deny_entry.wallet.as_ref()],
+        bump,
+        close = destination
+    )]
+    pub deny_entry: Account<'info, DenylistEntry>,
+
+    /// CHECK: Only receives the reclaimed rent lamports
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+
+    /// CHECK: The freeze authority or a multisig, checked by `verify_authority`
+    pub freeze_authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    #[account(init, payer = payer, space = 8 + Multisig::INIT_SPACE)]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddMinter<'info> {
+    #[account(
+        constraint = mint.authority.is_some() @ ErrorCode::MintAuthorityRevoked,
+        constraint = mint.authority == Some(authority.key()) @ ErrorCode::InvalidMintAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 32 + 8,
+        seeds = [b"minter", mint.key().as_ref(), minter.key().as_ref()],
+        bump
+    )]
+    pub minter_role: Account<'info, MinterRole>,
+
+    /// CHECK: This is just used as a seed for the PDA
+    pub minter: AccountInfo<'info>,
+
+    /// CHECK: The mint authority or a multisig, checked by `verify_authority`
+    pub authority: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinterAllowance<'info> {
+    #[account(
+        constraint = mint.authority.is_some() @ ErrorCode::MintAuthorityRevoked,
+        constraint = mint.authority == Some(authority.key()) @ ErrorCode::InvalidMintAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(mut, seeds = [b"minter", mint.key().as_ref(), minter_role.minter.as_ref()], bump)]
+    pub minter_role: Account<'info, MinterRole>,
+
+    /// CHECK: The mint authority or a multisig, checked by `verify_authority`
+    pub authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveMinter<'info> {
+    #[account(
+        constraint = mint.authority.is_some() @ ErrorCode::MintAuthorityRevoked,
+        constraint = mint.authority == Some(authority.key()) @ ErrorCode::InvalidMintAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(
+        mut,
+        seeds = [b"minter", mint.key().as_ref(), minter_role.minter.as_ref()],
+        bump,
+        close = destination
+    )]
+    pub minter_role: Account<'info, MinterRole>,
+
+    /// CHECK: Only receives the reclaimed rent lamports
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+
+    /// CHECK: The mint authority or a multisig, checked by `verify_authority`
+    pub authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct QueueMint<'info> {
+    #[account(
+        mut,
+        constraint = mint.authority.is_some() @ ErrorCode::MintAuthorityRevoked,
+        constraint = mint.authority == Some(authority.key()) @ ErrorCode::InvalidMintAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(seeds = [b"token", token_account.owner.as_ref(), mint.key().as_ref()], bump)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 8 + 32 + 8 + 8,
+        seeds = [b"pending_mint", mint.key().as_ref(), &mint.queued_mint_count.to_le_bytes()],
+        bump
+    )]
+    pub pending_mint: Account<'info, PendingMint>,
+
+    /// CHECK: The mint authority or a multisig, checked by `verify_authority`
+    pub authority: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteMint<'info> {
+    #[account(
+        mut,
+        constraint = mint.authority.is_some() @ ErrorCode::MintAuthorityRevoked,
+        constraint = mint.authority == Some(authority.key()) @ ErrorCode::InvalidMintAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(
+        mut,
+        seeds = [b"pending_mint", mint.key().as_ref(), &pending_mint.id.to_le_bytes()],
+        bump,
+        has_one = token_account,
+        close = destination
+    )]
+    pub pending_mint: Account<'info, PendingMint>,
+
+    #[account(mut)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Denylist entry of the recipient, only checked for existence
+    #[account(seeds = [b"deny", mint.key().as_ref(), token_account.owner.as_ref()], bump)]
+    pub deny_entry: AccountInfo<'info>,
+
+    /// CHECK: Only receives the reclaimed rent lamports
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+
+    /// CHECK: The mint authority or a multisig, checked by `verify_authority`
+    pub authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelMint<'info> {
+    #[account(
+        constraint = mint.authority.is_some() @ ErrorCode::MintAuthorityRevoked,
+        constraint = mint.authority == Some(authority.key()) @ ErrorCode::InvalidMintAuthority
+    )]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(
+        mut,
+        seeds = [b"pending_mint", mint.key().as_ref(), &pending_mint.id.to_le_bytes()],
+        bump,
+        close = destination
+    )]
+    pub pending_mint: Account<'info, PendingMint>,
+
+    /// CHECK: Only receives the reclaimed rent lamports
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+
+    /// CHECK: The mint authority or a multisig, checked by `verify_authority`
+    pub authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVesting<'info> {
+    #[account(mut)]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(mut, seeds = [b"token", grantor.key().as_ref(), mint.key().as_ref()], bump)]
+    pub source: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1,
+        seeds = [b"vesting", mint.key().as_ref(), &mint.vesting_count.to_le_bytes()],
+        bump
+    )]
+    pub vesting: Account<'info, VestingAccount>,
+
+    /// CHECK: Only recorded as the beneficiary, never read or written
+    pub beneficiary: AccountInfo<'info>,
+
+    /// CHECK: Denylist entry of the grantor, only checked for existence
+    #[account(seeds = [b"deny", mint.key().as_ref(), grantor.key().as_ref()], bump)]
+    pub deny_entry: AccountInfo<'info>,
+
+    /// CHECK: The owner of `source` or a multisig, checked by `verify_authority`
+    pub grantor: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting", mint.key().as_ref(), &vesting.id.to_le_bytes()],
+        bump,
+        has_one = beneficiary,
+        has_one = grantor
+    )]
+    pub vesting: Account<'info, VestingAccount>,
+
+    #[account(mut, seeds = [b"token", beneficiary.key().as_ref(), mint.key().as_ref()], bump)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Denylist entry of the beneficiary, only checked for existence
+    #[account(seeds = [b"deny", mint.key().as_ref(), beneficiary.key().as_ref()], bump)]
+    pub deny_entry: AccountInfo<'info>,
+
+    /// CHECK: The beneficiary or a multisig, checked by `verify_authority`
+    pub beneficiary: AccountInfo<'info>,
+
+    /// CHECK: Only receives the vesting account's rent once it is fully claimed
+    #[account(mut)]
+    pub grantor: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting", mint.key().as_ref(), &vesting.id.to_le_bytes()],
+        bump,
+        has_one = grantor
+    )]
+    pub vesting: Account<'info, VestingAccount>,
+
+    #[account(mut, seeds = [b"token", grantor.key().as_ref(), mint.key().as_ref()], bump)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Denylist entry of the grantor, only checked for existence
+    #[account(seeds = [b"deny", mint.key().as_ref(), grantor.key().as_ref()], bump)]
+    pub deny_entry: AccountInfo<'info>,
+
+    /// CHECK: The grantor or a multisig, checked by `verify_authority`; receives the
+    /// vesting account's rent if nothing is left to claim
+    #[account(mut)]
+    pub grantor: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct MakeOffer<'info> {
+    pub offer_mint: Account<'info, TokenMint>,
+
+    pub request_mint: Account<'info, TokenMint>,
+
+    #[account(mut, seeds = [b"token", maker.key().as_ref(), offer_mint.key().as_ref()], bump)]
+    pub source: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 8 + 32 + 8 + 32 + 8,
+        seeds = [b"offer", maker.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 32 + 8 + 1 + 33 + 8 + 8,
+        seeds = [b"token", offer.key().as_ref(), offer_mint.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    /// CHECK: Denylist entry of the maker, only checked for existence
+    #[account(seeds = [b"deny", offer_mint.key().as_ref(), maker.key().as_ref()], bump)]
+    pub deny_entry: AccountInfo<'info>,
+
+    /// CHECK: The owner of `source` or a multisig, checked by `verify_authority`
+    pub maker: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Boxed to keep this many accounts within the program's stack frame.
+#[derive(Accounts)]
+pub struct TakeOffer<'info> {
+    #[account(
+        mut,
+        seeds = [b"offer", maker.key().as_ref(), &offer.id.to_le_bytes()],
+        bump,
+        has_one = maker,
+        has_one = offer_mint,
+        has_one = request_mint,
+        close = maker
+    )]
+    pub offer: Box<Account<'info, Offer>>,
+
+    pub offer_mint: Box<Account<'info, TokenMint>>,
+
+    pub request_mint: Box<Account<'info, TokenMint>>,
+
+    #[account(
+        mut,
+        seeds = [b"token", offer.key().as_ref(), offer_mint.key().as_ref()],
+        bump,
+        close = maker
+    )]
+    pub escrow: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [b"token", maker.key().as_ref(), offer_mint.key().as_ref()], bump)]
+    pub maker_offer_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [b"token", maker.key().as_ref(), request_mint.key().as_ref()], bump)]
+    pub maker_request_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [b"token", taker.key().as_ref(), offer_mint.key().as_ref()], bump)]
+    pub taker_offer_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [b"token", taker.key().as_ref(), request_mint.key().as_ref()], bump)]
+    pub taker_request_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Denylist entry of the maker for the offered mint, only checked for existence
+    #[account(seeds = [b"deny", offer_mint.key().as_ref(), maker.key().as_ref()], bump)]
+    pub maker_offer_deny_entry: AccountInfo<'info>,
+
+    /// CHECK: Denylist entry of the maker for the requested mint, only checked for existence
+    #[account(seeds = [b"deny", request_mint.key().as_ref(), maker.key().as_ref()], bump)]
+    pub maker_request_deny_entry: AccountInfo<'info>,
+
+    /// CHECK: Denylist entry of the taker for the offered mint, only checked for existence
+    #[account(seeds = [b"deny", offer_mint.key().as_ref(), taker.key().as_ref()], bump)]
+    pub taker_offer_deny_entry: AccountInfo<'info>,
+
+    /// CHECK: Denylist entry of the taker for the requested mint, only checked for existence
+    #[account(seeds = [b"deny", request_mint.key().as_ref(), taker.key().as_ref()], bump)]
+    pub taker_request_deny_entry: AccountInfo<'info>,
+
+    /// CHECK: Only receives tokens and the rent of the offer and escrow accounts
+    #[account(mut)]
+    pub maker: AccountInfo<'info>,
+
+    // Taking one's own offer would let the credits overwrite the debits on exit.
+    /// CHECK: The owner of the taker's token accounts or a multisig, checked by `verify_authority`
+    #[account(constraint = taker.key() != maker.key() @ ErrorCode::SelfTransfer)]
+    pub taker: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOffer<'info> {
+    #[account(
+        mut,
+        seeds = [b"offer", maker.key().as_ref(), &offer.id.to_le_bytes()],
+        bump,
+        has_one = maker,
+        has_one = offer_mint,
+        close = maker
+    )]
+    pub offer: Account<'info, Offer>,
+
+    pub offer_mint: Account<'info, TokenMint>,
+
+    #[account(
+        mut,
+        seeds = [b"token", offer.key().as_ref(), offer_mint.key().as_ref()],
+        bump,
+        close = maker
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(mut, seeds = [b"token", maker.key().as_ref(), offer_mint.key().as_ref()], bump)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Denylist entry of the maker, only checked for existence
+    #[account(seeds = [b"deny", offer_mint.key().as_ref(), maker.key().as_ref()], bump)]
+    pub deny_entry: AccountInfo<'info>,
+
+    /// CHECK: The maker or a multisig, checked by `verify_authority`; receives the
+    /// rent of the offer and escrow accounts
+    #[account(mut)]
+    pub maker: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(merkle_root: [u8; 32])]
+pub struct CreateDistributor<'info> {
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(mut, seeds = [b"token", funder.key().as_ref(), mint.key().as_ref()], bump)]
+    pub source: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8,
+        seeds = [b"distributor", mint.key().as_ref(), merkle_root.as_ref()],
+        bump
+    )]
+    pub distributor: Account<'info, Distributor>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 32 + 8 + 1 + 33 + 8 + 8,
+        seeds = [b"token", distributor.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    /// CHECK: Denylist entry of the funder, only checked for existence
+    #[account(seeds = [b"deny", mint.key().as_ref(), funder.key().as_ref()], bump)]
+    pub deny_entry: AccountInfo<'info>,
+
+    /// CHECK: The owner of `source` or a multisig, checked by `verify_authority`
+    pub funder: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(chunk: u64)]
+pub struct CreateClaimBitmap<'info> {
+    #[account(mut)]
+    pub distributor: Account<'info, Distributor>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 8 + 4 + CLAIM_BITMAP_BYTES,
+        seeds = [b"claims", distributor.key().as_ref(), &chunk.to_le_bytes()],
+        bump
+    )]
+    pub claim_bitmap: Account<'info, ClaimBitmap>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Anyone may submit a claim: the tokens can only go to the token account of the
+// wallet in the proven leaf.
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct Claim<'info> {
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(
+        mut,
+        seeds = [b"distributor", mint.key().as_ref(), distributor.merkle_root.as_ref()],
+        bump
+    )]
+    pub distributor: Account<'info, Distributor>,
+
+    #[account(mut, seeds = [b"token", distributor.key().as_ref(), mint.key().as_ref()], bump)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"claims", distributor.key().as_ref(), &(index / CLAIMS_PER_BITMAP).to_le_bytes()],
+        bump
+    )]
+    pub claim_bitmap: Account<'info, ClaimBitmap>,
+
+    #[account(mut, seeds = [b"token", token_account.owner.as_ref(), mint.key().as_ref()], bump)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Denylist entry of the claimant, only checked for existence
+    #[account(seeds = [b"deny", mint.key().as_ref(), token_account.owner.as_ref()], bump)]
+    pub deny_entry: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(chunk: u64)]
+pub struct CloseClaimBitmap<'info> {
+    #[account(mut, has_one = funder)]
+    pub distributor: Account<'info, Distributor>,
+
+    #[account(
+        mut,
+        seeds = [b"claims", distributor.key().as_ref(), &chunk.to_le_bytes()],
+        bump,
+        close = funder
+    )]
+    pub claim_bitmap: Account<'info, ClaimBitmap>,
+
+    /// CHECK: The funder or a multisig, checked by `verify_authority`; receives the
+    /// bitmap's rent
+    #[account(mut)]
+    pub funder: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseDistributor<'info> {
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(
+        mut,
+        seeds = [b"distributor", mint.key().as_ref(), distributor.merkle_root.as_ref()],
+        bump,
+        has_one = funder,
+        close = funder
+    )]
+    pub distributor: Account<'info, Distributor>,
+
+    #[account(
+        mut,
+        seeds = [b"token", distributor.key().as_ref(), mint.key().as_ref()],
+        bump,
+        close = funder
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(mut, seeds = [b"token", funder.key().as_ref(), mint.key().as_ref()], bump)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Denylist entry of the funder, only checked for existence
+    #[account(seeds = [b"deny", mint.key().as_ref(), funder.key().as_ref()], bump)]
+    pub deny_entry: AccountInfo<'info>,
+
+    /// CHECK: The funder or a multisig, checked by `verify_authority`; receives the
+    /// rent of the distributor and vault accounts
+    #[account(mut)]
+    pub funder: AccountInfo<'info>,
+}
+
+#[account]
+pub struct TokenMint {
+    /// `None` once the authority has been revoked and the supply is fixed.
+    pub authority: Option<Pubkey>,
+    pub total_supply: u64,
+    pub decimals: u8,
+    /// Proposed authority awaiting `accept_mint_authority`.
+    pub pending_authority: Option<Pubkey>,
+    /// `None` if token accounts of this mint can never be frozen.
+    pub freeze_authority: Option<Pubkey>,
+    /// Hard cap on `total_supply`, `None` if uncapped.
+    pub max_supply: Option<u64>,
+    /// `None` if this mint can never be paused.
+    pub pause_authority: Option<Pubkey>,
+    /// While set, minting, transfers and burns all fail.
+    pub paused: bool,
+    /// `None` if no transfer fee can ever be charged.
+    pub fee_authority: Option<Pubkey>,
+    pub transfer_fee_basis_points: u16,
+    /// Upper bound on the fee charged for a single transfer.
+    pub maximum_fee: u64,
+    /// Fees harvested from token accounts, awaiting `withdraw_withheld`.
+    pub withheld_amount: u64,
+    /// Tokens can be minted and burned but never transferred.
+    pub non_transferable: bool,
+    /// May transfer or burn from any token account of this mint without the owner's signature.
+    pub permanent_delegate: Option<Pubkey>,
+    /// State given to newly created token accounts.
+    pub default_account_state: AccountState,
+    /// Seconds a queued mint must wait before `execute_mint`; while non-zero, tokens
+    /// can only be minted through the queue.
+    pub mint_delay: i64,
+    /// Number of mints ever queued, used as the id of the next `PendingMint`.
+    pub queued_mint_count: u64,
+    /// Most tokens that can be minted per window, `None` if unlimited.
+    pub mint_rate_limit: Option<u64>,
+    /// Length of a rate limit window in seconds.
+    pub mint_rate_window: i64,
+    /// Unix timestamp of the last mint counted against the rate limit.
+    pub rate_limit_updated_at: i64,
+    /// Rate limit capacity in use as of `rate_limit_updated_at`.
+    pub rate_limit_used: u64,
+    /// Number of vesting schedules ever created, used as the id of the next `VestingAccount`.
+    pub vesting_count: u64,
+    /// Shorter `mint_delay` awaiting `apply_mint_delay`, `None` if no change is pending.
+    pub pending_mint_delay: Option<i64>,
+    /// Unix timestamp from which `pending_mint_delay` can be applied.
+    pub mint_delay_change_after: i64,
+}
+
+impl TokenMint {
+    /// Rate limit capacity in use at `now`. Minting uses up capacity, which frees up
+    /// gradually at `limit` per window: at most `limit` can be minted at once, and no
+    /// window boundary makes the full limit available again all at once.
+    pub fn mint_capacity_used(&self, now: i64) -> u64 {
+        let Some(limit) = self.mint_rate_limit else {
+            return 0;
+        };
+
+        let elapsed = now.saturating_sub(self.rate_limit_updated_at).max(0) as u128;
+        let freed = limit as u128 * elapsed / self.mint_rate_window.max(1) as u128;
+
+        (self.rate_limit_used as u128).saturating_sub(freed) as u64
+    }
+}
+
+#[account]
+pub struct TokenAccount {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub state: AccountState,
+    pub delegate: Option<Pubkey>,
+    /// Remaining allowance of `delegate`.
+    pub delegated_amount: u64,
+    /// Transfer fees received but not yet harvested; not spendable by the owner.
+    pub withheld_amount: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TokenMetadata {
+    pub mint: Pubkey,
+    /// `None` once the metadata has been made immutable.
+    pub update_authority: Option<Pubkey>,
+    #[max_len(MAX_NAME_LEN)]
+    pub name: String,
+    #[max_len(MAX_SYMBOL_LEN)]
+    pub symbol: String,
+    #[max_len(MAX_URI_LEN)]
+    pub uri: String,
+    #[max_len(MAX_METADATA_FIELDS)]
+    pub additional_metadata: Vec<MetadataField>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct MetadataField {
+    #[max_len(MAX_METADATA_KEY_LEN)]
+    pub key: String,
+    #[max_len(MAX_METADATA_VALUE_LEN)]
+    pub value: String,
+}
+
+/// Exists while `wallet` is barred from minting, transferring or burning tokens of `mint`.
+#[account]
+pub struct DenylistEntry {
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+}
+
+/// An M-of-N authority, usable as a mint, freeze or token account owner authority.
+/// Acting as it requires `threshold` of `signers` to sign the transaction.
+#[account]
+#[derive(InitSpace)]
+pub struct Multisig {
+    pub threshold: u8,
+    #[max_len(MAX_MULTISIG_SIGNERS)]
+    pub signers: Vec<Pubkey>,
+}
+
+/// Exists once `owner` has used or cancelled `nonce` in a permit for `mint`, so it
+/// cannot be replayed.
+#[account]
+pub struct PermitNonce {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub nonce: u64,
+}
+
+/// Lets `minter` mint up to `allowance` tokens of `mint` without the mint authority.
+#[account]
+pub struct MinterRole {
+    pub mint: Pubkey,
+    pub minter: Pubkey,
+    /// Tokens the minter may still mint; each `mint_tokens` call consumes from it.
+    pub allowance: u64,
+}
+
+/// A mint queued by `queue_mint`, executable by the mint authority once
+/// `execute_after` has passed.
+#[account]
+pub struct PendingMint {
+    pub mint: Pubkey,
+    /// Position in the mint's queue, part of the address seeds.
+    pub id: u64,
+    pub token_account: Pubkey,
+    pub amount: u64,
+    /// Unix timestamp from which the mint can be executed.
+    pub execute_after: i64,
+}
+
+/// Tokens escrowed by `create_vesting` for `beneficiary`: nothing unlocks before
+/// `cliff_time`, then they unlock linearly from `start_time` until all are at `end_time`.
+#[account]
+pub struct VestingAccount {
+    pub mint: Pubkey,
+    /// Position among the mint's vesting schedules, part of the address seeds.
+    pub id: u64,
+    pub grantor: Pubkey,
+    pub beneficiary: Pubkey,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub end_time: i64,
+    /// Whether the grantor may take back the unvested tokens with `revoke_vesting`.
+    pub revocable: bool,
+}
+
+impl VestingAccount {
+    /// Tokens unlocked at unix time `now`, including those already claimed.
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        if now >= self.end_time {
+            return self.total_amount;
+        }
+        if now < self.cliff_time {
+            return 0;
+        }
+
+        let elapsed = now.abs_diff(self.start_time) as u128;
+        let duration = self.end_time.abs_diff(self.start_time) as u128;
+        // `elapsed < duration`, so the result is below `total_amount` and fits a u64.
+        (self.total_amount as u128 * elapsed / duration) as u64
+    }
+}
+
+/// An offer to swap `offer_amount` of `offer_mint`, held in escrow, for
+/// `request_amount` of `request_mint`.
+#[account]
+pub struct Offer {
+    pub maker: Pubkey,
+    /// Chosen by the maker, part of the address seeds.
+    pub id: u64,
+    pub offer_mint: Pubkey,
+    pub offer_amount: u64,
+    pub request_mint: Pubkey,
+    pub request_amount: u64,
+}
+
+/// Airdrop of `total_amount` tokens of `mint`, held by its vault token account, to
+/// the recipients committed to by `merkle_root`.
+#[account]
+pub struct Distributor {
+    pub mint: Pubkey,
+    pub merkle_root: [u8; 32],
+    /// Wallet whose tokens funded the vault.
+    pub funder: Pubkey,
+    /// Number of leaves in the tree; claim indices run from 0 to `num_claims - 1`.
+    pub num_claims: u64,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    /// Unix timestamp from which claims fail and the funder may close the distributor.
+    pub end_time: i64,
+    /// Claim bitmaps created and not yet closed; the distributor outlives them all.
+    pub open_bitmaps: u64,
+}
+
+/// Records which of the `CLAIMS_PER_BITMAP` claims starting at
+/// `chunk * CLAIMS_PER_BITMAP` have been made, one bit each.
+#[account]
+pub struct ClaimBitmap {
+    pub distributor: Pubkey,
+    pub chunk: u64,
+    pub bits: Vec<u8>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AccountState {
+    Initialized,
+    Frozen,
+}
+
+#[event]
+pub struct MintInitialized {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub decimals: u8,
+    pub freeze_authority: Option<Pubkey>,
+    pub max_supply: Option<u64>,
+    pub pause_authority: Option<Pubkey>,
+    pub fee_authority: Option<Pubkey>,
+    pub non_transferable: bool,
+    pub permanent_delegate: Option<Pubkey>,
+    pub default_account_state: AccountState,
+}
+
+#[event]
+pub struct MintAuthorityChanged {
+    pub mint: Pubkey,
+    pub old_authority: Option<Pubkey>,
+    /// `None` when the authority was revoked.
+    pub new_authority: Option<Pubkey>,
+}
+
+#[event]
+pub struct MintAuthorityProposed {
+    pub mint: Pubkey,
+    /// `None` when a pending handover was cancelled.
+    pub pending_authority: Option<Pubkey>,
+}
+
+#[event]
+pub struct TokenAccountCreated {
+    pub token_account: Pubkey,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub state: AccountState,
+}
+
+#[event]
+pub struct TokenAccountClosed {
+    pub token_account: Pubkey,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub destination: Pubkey,
+}
+
+#[event]
+pub struct TokensMinted {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub amount: u64,
+    pub total_supply: u64,
+}
+
+#[event]
+pub struct Transferred {
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct Burned {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub total_supply: u64,
+}
+
+#[event]
+pub struct DelegateApproved {
+    pub token_account: Pubkey,
+    pub delegate: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PermitCancelled {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct DelegateRevoked {
+    pub token_account: Pubkey,
+}
+
+#[event]
+pub struct TokenAccountFrozen {
+    pub token_account: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct TokenAccountThawed {
+    pub token_account: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct Paused {
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct Unpaused {
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct MetadataUpdated {
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+#[event]
+pub struct MetadataFieldSet {
+    pub mint: Pubkey,
+    pub key: String,
+    pub value: String,
+}
+
+#[event]
+pub struct MetadataFieldRemoved {
+    pub mint: Pubkey,
+    pub key: String,
+}
+
+#[event]
+pub struct MetadataUpdateAuthorityChanged {
+    pub mint: Pubkey,
+    pub new_authority: Option<Pubkey>,
+}
+
+/// Emitted alongside `Transferred` or `Burned` whenever the permanent delegate
+/// moves tokens out of an account it does not own.
+#[event]
+pub struct ClawedBack {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub permanent_delegate: Pubkey,
+    /// `None` when the tokens were burned.
+    pub destination: Option<Pubkey>,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TransferFeeSet {
+    pub mint: Pubkey,
+    pub basis_points: u16,
+    pub maximum_fee: u64,
+}
+
+#[event]
+pub struct WithheldHarvested {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct WithheldWithdrawn {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct DenylistAdded {
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+}
+
+#[event]
+pub struct DenylistRemoved {
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+}
+
+#[event]
+pub struct MultisigCreated {
+    pub multisig: Pubkey,
+    pub threshold: u8,
+    pub signers: Vec<Pubkey>,
+}
+
+#[event]
+pub struct MintDelaySet {
+    pub mint: Pubkey,
+    pub delay: i64,
+}
+
+#[event]
+pub struct MintDelayChangeQueued {
+    pub mint: Pubkey,
+    pub delay: i64,
+    pub apply_after: i64,
+}
+
+#[event]
+pub struct MinterAllowanceSet {
+    pub mint: Pubkey,
+    pub minter: Pubkey,
+    pub allowance: u64,
+}
+
+#[event]
+pub struct MinterRemoved {
+    pub mint: Pubkey,
+    pub minter: Pubkey,
+}
+
+#[event]
+pub struct MintRateLimitSet {
+    pub mint: Pubkey,
+    pub limit: u64,
+    pub window: i64,
+}
+
+#[event]
+pub struct MintQueued {
+    pub mint: Pubkey,
+    pub pending_mint: Pubkey,
+    pub token_account: Pubkey,
+    pub amount: u64,
+    pub execute_after: i64,
+}
+
+#[event]
+pub struct QueuedMintExecuted {
+    pub mint: Pubkey,
+    pub pending_mint: Pubkey,
+}
+
+#[event]
+pub struct QueuedMintCancelled {
+    pub mint: Pubkey,
+    pub pending_mint: Pubkey,
+}
+
+#[event]
+pub struct VestingCreated {
+    pub mint: Pubkey,
+    pub vesting: Pubkey,
+    pub grantor: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub end_time: i64,
+    pub revocable: bool,
+}
+
+#[event]
+pub struct VestedClaimed {
+    pub mint: Pubkey,
+    pub vesting: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    /// Part of `amount` withheld on the beneficiary's token account.
+    pub fee: u64,
+}
+
+#[event]
+pub struct VestingRevoked {
+    pub mint: Pubkey,
+    pub vesting: Pubkey,
+    pub grantor: Pubkey,
+    /// Unvested tokens returned to the grantor.
+    pub returned_amount: u64,
+}
+
+#[event]
+pub struct OfferMade {
+    pub offer: Pubkey,
+    pub maker: Pubkey,
+    pub offer_mint: Pubkey,
+    pub offer_amount: u64,
+    pub request_mint: Pubkey,
+    pub request_amount: u64,
+}
+
+#[event]
+pub struct OfferTaken {
+    pub offer: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    /// Transfer fee withheld from the offered tokens.
+    pub offer_fee: u64,
+    /// Transfer fee withheld from the requested tokens.
+    pub request_fee: u64,
+}
+
+#[event]
+pub struct OfferCancelled {
+    pub offer: Pubkey,
+    pub maker: Pubkey,
+}
+
+#[event]
+pub struct DistributorCreated {
+    pub mint: Pubkey,
+    pub distributor: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub num_claims: u64,
+    pub total_amount: u64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct AirdropClaimed {
+    pub mint: Pubkey,
+    pub distributor: Pubkey,
+    pub claimant: Pubkey,
+    pub index: u64,
+    pub amount: u64,
+    /// Part of `amount` withheld on the claimant's token account.
+    pub fee: u64,
+}
+
+#[event]
+pub struct DistributorClosed {
+    pub mint: Pubkey,
+    pub distributor: Pubkey,
+    pub funder: Pubkey,
+    /// Unclaimed tokens returned to the funder.
+    pub returned_amount: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Token account mint does not match the provided mint")]
+    MintMismatch,
+    #[msg("Arithmetic overflow")]
+    Overflow,
+    #[msg("Sender has insufficient funds")]
+    InsufficientFunds,
+    #[msg("Mint authority has been revoked")]
+    MintAuthorityRevoked,
+    #[msg("Signer is not the mint authority")]
+    InvalidMintAuthority,
+    #[msg("No mint authority handover is pending")]
+    NoPendingAuthority,
+    #[msg("Signer is not the pending mint authority")]
+    InvalidPendingAuthority,
+    #[msg("Token account is frozen")]
+    AccountFrozen,
+    #[msg("Token account is not frozen")]
+    AccountNotFrozen,
+    #[msg("Mint has no freeze authority")]
+    NoFreezeAuthority,
+    #[msg("Signer is not the freeze authority")]
+    InvalidFreezeAuthority,
+    #[msg("Signer is neither the owner nor the delegate of the token account")]
+    InvalidTokenAuthority,
+    #[msg("Delegate allowance is insufficient")]
+    InsufficientAllowance,
+    #[msg("Token account still holds tokens")]
+    NonZeroBalance,
+    #[msg("Source and destination token accounts must differ")]
+    SelfTransfer,
+    #[msg("Minting would exceed the maximum supply")]
+    MaxSupplyExceeded,
+    #[msg("Mint is paused")]
+    MintPaused,
+    #[msg("Mint is not paused")]
+    MintNotPaused,
+    #[msg("Mint has no pause authority")]
+    NoPauseAuthority,
+    #[msg("Signer is not the pause authority")]
+    InvalidPauseAuthority,
+    #[msg("Metadata value exceeds its maximum length")]
+    MetadataTooLong,
+    #[msg("Metadata already holds the maximum number of fields")]
+    TooManyMetadataFields,
+    #[msg("Metadata field not found")]
+    MetadataFieldNotFound,
+    #[msg("Metadata is immutable")]
+    MetadataImmutable,
+    #[msg("Signer is not the metadata update authority")]
+    InvalidUpdateAuthority,
+    #[msg("Transfer fee cannot exceed 10000 basis points")]
+    InvalidTransferFee,
+    #[msg("Mint has no fee authority")]
+    NoFeeAuthority,
+    #[msg("Signer is not the fee authority")]
+    InvalidFeeAuthority,
+    #[msg("No withheld fees to collect")]
+    NoWithheldFees,
+    #[msg("Tokens of this mint are non-transferable")]
+    NonTransferable,
+    #[msg("Wallet is on the mint's denylist")]
+    WalletDenied,
+    #[msg("Expected a token account and denylist entry for each amount")]
+    RecipientAccountsMismatch,
+    #[msg("Multisig threshold must be between 1 and the number of distinct signers")]
+    InvalidMultisig,
+    #[msg("Not enough multisig signers")]
+    NotEnoughSigners,
+    #[msg("Mint has a delay; queue the mint with queue_mint instead")]
+    MintTimelocked,
+    #[msg("Queued mint's delay has not passed yet")]
+    MintNotReady,
+    #[msg("Mint delay cannot be negative")]
+    InvalidMintDelay,
+    #[msg("Mint rate limit exceeded for the current window")]
+    MintRateLimitExceeded,
+    #[msg("Mint rate limit can only be tightened and needs a positive window")]
+    InvalidMintRateLimit,
+    #[msg("Mint amount exceeds the minter's allowance")]
+    MinterAllowanceExceeded,
+    #[msg("Permit deadline has passed")]
+    PermitExpired,
+    #[msg("Missing or invalid Ed25519 signature of the token account owner")]
+    InvalidPermitSignature,
+    #[msg("Vesting needs a positive amount and start <= cliff <= end, with start before end")]
+    InvalidVestingSchedule,
+    #[msg("No vested tokens to claim")]
+    NothingToClaim,
+    #[msg("Vesting schedule is not revocable")]
+    VestingNotRevocable,
+    #[msg("Offer needs positive amounts of two different transferable mints")]
+    InvalidOffer,
+    #[msg("Distributor needs at least one claim, a positive amount and an end time in the future")]
+    InvalidDistributor,
+    #[msg("Claim index is beyond the distributor's claims")]
+    ClaimIndexOutOfRange,
+    #[msg("Merkle proof does not match the distributor's root")]
+    InvalidMerkleProof,
+    #[msg("Airdrop has already been claimed")]
+    AlreadyClaimed,
+    #[msg("Airdrop has ended")]
+    AirdropEnded,
+    #[msg("Airdrop has not ended yet")]
+    AirdropNotEnded,
+    #[msg("No shorter mint delay is pending, or the current delay has not passed yet")]
+    MintDelayChangeNotReady,
+    #[msg("Distributor still has open claim bitmaps; close them first")]
+    ClaimBitmapsOpen,
+}
+
+/// Bytes a token account owner signs to let `spender` spend `amount` of `mint` via `permit`.
+pub fn permit_message(
+    mint: &Pubkey,
+    owner: &Pubkey,
+    spender: &Pubkey,
+    amount: u64,
+    nonce: u64,
+    deadline: i64,
+) -> Vec<u8> {
+    [
+        PERMIT_DOMAIN,
+        crate::ID.as_ref(),
+        mint.as_ref(),
+        owner.as_ref(),
+        spender.as_ref(),
+        &amount.to_le_bytes(),
+        &nonce.to_le_bytes(),
+        &deadline.to_le_bytes(),
+    ]
+    .concat()
+}
+
+/// Leaf of a distributor's merkle tree for `amount` tokens to `claimant`; the index
+/// ties each leaf to its own bit in the claim bitmaps.
+pub fn airdrop_leaf(index: u64, claimant: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[
+        &[0],
+        &index.to_le_bytes(),
+        claimant.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Parent of two merkle tree nodes. The pair is sorted so proofs need not say which
+/// side each sibling is on, and the prefix keeps a node from passing as a leaf.
+pub fn merkle_parent(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[1], left, right]).to_bytes()
+}
+
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    proof
+        .iter()
+        .fold(leaf, |node, sibling| merkle_parent(&node, sibling))
+        == *root
+}
+
+/// Checks that the instruction before the current one is an Ed25519 program
+/// instruction verifying a signature by `signer` over exactly `message`.
+fn verify_ed25519_signature(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions)?;
+    require!(current_index > 0, ErrorCode::InvalidPermitSignature);
+    let instruction = load_instruction_at_checked(current_index as usize - 1, instructions)?;
+    require_keys_eq!(
+        instruction.program_id,
+        ed25519_program::ID,
+        ErrorCode::InvalidPermitSignature
+    );
+
+    // A count byte and padding, then one set of offsets: signature, signature
+    // instruction index, public key, public key instruction index, message,
+    // message size, message instruction index.
+    let data = &instruction.data;
+    require!(
+        data.len() >= 16 && data[0] == 1,
+        ErrorCode::InvalidPermitSignature
+    );
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+
+    // The signature, key and message must all live in the Ed25519 instruction itself.
+    require!(
+        [4, 8, 14].iter().all(|&at| read_u16(at) == u16::MAX),
+        ErrorCode::InvalidPermitSignature
+    );
+
+    let public_key_offset = read_u16(6) as usize;
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    require!(
+        data.get(public_key_offset..public_key_offset + 32) == Some(signer.as_ref())
+            && data.get(message_offset..message_offset + message_size) == Some(message),
+        ErrorCode::InvalidPermitSignature
+    );
+
+    Ok(())
+}
+
+/// Counts `amount` against the mint's rate limit.
+fn consume_mint_capacity(mint: &mut TokenMint, amount: u64) -> Result<()> {
+    let Some(limit) = mint.mint_rate_limit else {
+        return Ok(());
+    };
+
+    let now = Clock::get()?.unix_timestamp;
+    let used = mint.mint_capacity_used(now);
+
+    let remaining = limit.saturating_sub(used);
+    if amount > remaining {
+        msg!(
+            "Requested {} tokens but only {} of {} are available, refilling at {} per {} seconds",
+            amount,
+            remaining,
+            limit,
+            limit,
+            mint.mint_rate_window
+        );
+        return err!(ErrorCode::MintRateLimitExceeded);
+    }
+
+    mint.rate_limit_used = used.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    mint.rate_limit_updated_at = now;
+
+    Ok(())
+}
+
+/// Checks that `authority` signed the transaction, or that it is a multisig and at
+/// least `threshold` of its members are among the signing `signers`.
+fn verify_authority(authority: &AccountInfo, signers: &[AccountInfo]) -> Result<()> {
+    if authority.is_signer {
+        return Ok(());
+    }
+
+    require_keys_eq!(
+        *authority.owner,
+        crate::ID,
+        anchor_lang::error::ErrorCode::AccountNotSigner
+    );
+    let multisig = Multisig::try_deserialize(&mut &authority.try_borrow_data()?[..])
+        .map_err(|_| error!(anchor_lang::error::ErrorCode::AccountNotSigner))?;
+
+    let approvals = multisig
+        .signers
+        .iter()
+        .filter(|member| {
+            signers
+                .iter()
+                .any(|signer| signer.is_signer && signer.key == *member)
+        })
+        .count();
+    require!(
+        approvals >= multisig.threshold as usize,
+        ErrorCode::NotEnoughSigners
+    );
+
+    Ok(())
+}
+
+/// Whether `authority` is spending from `token_account` as the mint's permanent
+/// delegate. Such spends bypass the owner and any approved allowance.
+fn is_permanent_delegate(
+    mint: &TokenMint,
+    token_account: &TokenAccount,
+    authority: Pubkey,
+) -> bool {
+    authority != token_account.owner && mint.permanent_delegate == Some(authority)
+}
+
+/// Checks that `authority` may move `amount` out of `token_account`, consuming
+/// the allowance when it is acting as the delegate.
+fn authorize_spend(token_account: &mut TokenAccount, authority: Pubkey, amount: u64) -> Result<()> {
+    if authority == token_account.owner {
+        return Ok(());
+    }
+
+    require!(
+        token_account.delegate == Some(authority),
+        ErrorCode::InvalidTokenAuthority
+    );
+    require!(
+        token_account.delegated_amount >= amount,
+        ErrorCode::InsufficientAllowance
+    );
+
+    token_account.delegated_amount = token_account
+        .delegated_amount
+        .checked_sub(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    // An exhausted allowance clears the delegate entirely.
+    if token_account.delegated_amount == 0 {
+        token_account.delegate = None;
+    }
+
+    Ok(())
+}
+
+fn validate_metadata(name: &str, symbol: &str, uri: &str) -> Result<()> {
+    require!(
+        name.len() <= MAX_NAME_LEN && symbol.len() <= MAX_SYMBOL_LEN && uri.len() <= MAX_URI_LEN,
+        ErrorCode::MetadataTooLong
+    );
+
+    Ok(())
+}
+
+/// Fee charged on a transfer of `amount`, rounded up and capped at the mint's maximum.
+fn calculate_transfer_fee(mint: &TokenMint, amount: u64) -> u64 {
+    let fee = (amount as u128 * mint.transfer_fee_basis_points as u128)
+        .div_ceil(MAX_FEE_BASIS_POINTS as u128);
+
+    // `fee <= amount` since the rate is at most 100%, so the cast cannot truncate.
+    (fee as u64).min(mint.maximum_fee)
+}
+
+/// Credits `amount` to `to`, holding back `fee` as withheld like a transfer does.
+fn credit_with_fee(to: &mut TokenAccount, amount: u64, fee: u64) -> Result<()> {
+    to.amount = to
+        .amount
+        .checked_add(amount - fee)
+        .ok_or(ErrorCode::Overflow)?;
+    to.withheld_amount = to
+        .withheld_amount
+        .checked_add(fee)
+        .ok_or(ErrorCode::Overflow)?;
+
+    Ok(())
+}
+
+/// Empties an escrow about to be closed into the maker's token account, withheld
+/// fees included, so no tokens disappear with it.
+fn refund_escrow(escrow: &mut TokenAccount, to: &mut TokenAccount) -> Result<()> {
+    to.amount = to
+        .amount
+        .checked_add(escrow.amount)
+        .ok_or(ErrorCode::Overflow)?;
+    to.withheld_amount = to
+        .withheld_amount
+        .checked_add(escrow.withheld_amount)
+        .ok_or(ErrorCode::Overflow)?;
+    escrow.amount = 0;
+    escrow.withheld_amount = 0;
+
+    Ok(())
+}
+
+/// Denylist entries only exist while the wallet is denied; `remove_from_denylist`
+/// closes them, leaving no data behind.
+fn require_not_denied(deny_entry: &AccountInfo) -> Result<()> {
+    require!(deny_entry.data_is_empty(), ErrorCode::WalletDenied);
+
+    Ok(())
+}
+
+/// Deserializes a token account of `mint` passed through `remaining_accounts`,
+/// applying the checks a `seeds` constraint would.
+fn load_token_account<'info>(
+    info: &'info AccountInfo<'info>,
+    mint: Pubkey,
+) -> Result<Account<'info, TokenAccount>> {
+    require!(
+        info.is_writable,
+        anchor_lang::error::ErrorCode::ConstraintMut
+    );
+
+    let token_account = Account::<TokenAccount>::try_from(info)?;
+    require!(token_account.mint == mint, ErrorCode::MintMismatch);
+
+    let (expected, _) = Pubkey::find_program_address(
+        &[b"token", token_account.owner.as_ref(), mint.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(
+        info.key(),
+        expected,
+        anchor_lang::error::ErrorCode::ConstraintSeeds
+    );
+
+    Ok(token_account)
+}
+
+/// `require_not_denied` for a denylist entry passed through `remaining_accounts`,
+/// whose address has not been checked by a `seeds` constraint.
+fn require_not_denied_at(deny_entry: &AccountInfo, mint: Pubkey, wallet: Pubkey) -> Result<()> {
+    let (expected, _) =
+        Pubkey::find_program_address(&[b"deny", mint.as_ref(), wallet.as_ref()], &crate::ID);
+    require_keys_eq!(
+        deny_entry.key(),
+        expected,
+        anchor_lang::error::ErrorCode::ConstraintSeeds
+    );
+
+    require_not_denied(deny_entry)
 }
@@ -6,8 +6,54 @@ import {
   mintTokens,
   transferTokens,
   burnTokens,
+  setMintAuthority,
+  revokeMintAuthority,
+  proposeMintAuthority,
+  acceptMintAuthority,
+  cancelMintAuthorityTransfer,
+  freezeAccount,
+  thawAccount,
+  approve,
+  permit,
+  cancelPermit,
+  revoke,
+  closeTokenAccount,
+  pause,
+  unpause,
+  initializeMetadata,
+  updateMetadata,
+  setMetadataField,
+  removeMetadataField,
+  setMetadataUpdateAuthority,
+  setTransferFee,
+  harvestWithheld,
+  withdrawWithheld,
+  addToDenylist,
+  removeFromDenylist,
+  mintToMany,
+  transferMany,
+  createMultisig,
+  setMintDelay,
+  applyMintDelay,
+  queueMint,
+  executeMint,
+  cancelMint,
+  setMintRateLimit,
+  mintAsMinter,
+  addMinter,
+  setMinterAllowance,
+  removeMinter,
+  createVesting,
+  claimVested,
+  revokeVesting,
+  makeOffer,
+  takeOffer,
+  cancelOffer,
+  createDistributor,
+  claimAirdrop,
+  closeDistributor,
 } from "./utils";
-import { Keypair } from "@solana/web3.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
 
 describe("solana-token", () => {
   const decimals = 9;
@@ -247,6 +293,233 @@ describe("solana-token", () => {
     }
   });
 
+  it("Should not transfer to the source account itself", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const sender = Keypair.generate();
+    const senderTokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      sender.publicKey,
+      authority.publicKey
+    );
+    await mintTokens(
+      program,
+      mintAddress,
+      senderTokenAccount,
+      authority.publicKey,
+      1000
+    );
+
+    try {
+      await transferTokens(
+        program,
+        senderTokenAccount,
+        senderTokenAccount,
+        sender,
+        100
+      );
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "SelfTransfer");
+      console.log("Attempted transfer to the source account failed.");
+    }
+
+    const senderData = await program.account.tokenAccount.fetch(
+      senderTokenAccount
+    );
+    assert.equal(senderData.amount.toNumber(), 1000);
+  });
+
+  it("Should not transfer to a token account of another mint", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const sender = Keypair.generate();
+    const senderTokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      sender.publicKey,
+      authority.publicKey
+    );
+    await mintTokens(
+      program,
+      mintAddress,
+      senderTokenAccount,
+      authority.publicKey,
+      1000
+    );
+
+    const otherMint = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+
+    const otherTokenAccount = await createTokenAccount(
+      program,
+      otherMint,
+      Keypair.generate().publicKey,
+      authority.publicKey
+    );
+
+    try {
+      await transferTokens(
+        program,
+        senderTokenAccount,
+        otherTokenAccount,
+        sender,
+        100
+      );
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "MintMismatch");
+      console.log("Attempted transfer to another mint's account failed.");
+    }
+
+    const senderData = await program.account.tokenAccount.fetch(
+      senderTokenAccount
+    );
+    assert.equal(senderData.amount.toNumber(), 1000);
+  });
+
+  it("Should not transfer to a mint account posing as a token account", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const sender = Keypair.generate();
+    const senderTokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      sender.publicKey,
+      authority.publicKey
+    );
+    await mintTokens(
+      program,
+      mintAddress,
+      senderTokenAccount,
+      authority.publicKey,
+      1000
+    );
+
+    try {
+      await transferTokens(
+        program,
+        senderTokenAccount,
+        mintAddress,
+        sender,
+        100
+      );
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "AccountDiscriminatorMismatch");
+      console.log("Attempted transfer to a mint account failed.");
+    }
+
+    const senderData = await program.account.tokenAccount.fetch(
+      senderTokenAccount
+    );
+    assert.equal(senderData.amount.toNumber(), 1000);
+  });
+
+  it("Should not transfer to a wallet address", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const sender = Keypair.generate();
+    const senderTokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      sender.publicKey,
+      authority.publicKey
+    );
+    await mintTokens(
+      program,
+      mintAddress,
+      senderTokenAccount,
+      authority.publicKey,
+      1000
+    );
+
+    try {
+      await transferTokens(
+        program,
+        senderTokenAccount,
+        authority.publicKey,
+        sender,
+        100
+      );
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "AccountOwnedByWrongProgram");
+      console.log("Attempted transfer to a wallet address failed.");
+    }
+
+    const senderData = await program.account.tokenAccount.fetch(
+      senderTokenAccount
+    );
+    assert.equal(senderData.amount.toNumber(), 1000);
+  });
+
+  it("Should not transfer to an uninitialized address", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const sender = Keypair.generate();
+    const senderTokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      sender.publicKey,
+      authority.publicKey
+    );
+    await mintTokens(
+      program,
+      mintAddress,
+      senderTokenAccount,
+      authority.publicKey,
+      1000
+    );
+
+    const [uninitialized] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("token"),
+        Keypair.generate().publicKey.toBuffer(),
+        mintAddress.toBuffer(),
+      ],
+      program.programId
+    );
+
+    try {
+      await transferTokens(
+        program,
+        senderTokenAccount,
+        uninitialized,
+        sender,
+        100
+      );
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "AccountNotInitialized");
+      console.log("Attempted transfer to an uninitialized account failed.");
+    }
+
+    const senderData = await program.account.tokenAccount.fetch(
+      senderTokenAccount
+    );
+    assert.equal(senderData.amount.toNumber(), 1000);
+  });
+
   it("Should not burn more than the balance", async () => {
     const mintAddress = await initializeMint(
       program,
@@ -269,4 +542,1972 @@ describe("solana-token", () => {
       console.log("Attempted burn more than balance failed.");
     }
   });
+
+  it("Should rotate the mint authority", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const user = Keypair.generate();
+    const tokenAccount = await createTokenAccount(program, mintAddress, user.publicKey, authority.publicKey);
+    const newAuthority = Keypair.generate();
+
+    await setMintAuthority(program, mintAddress, authority.publicKey, newAuthority.publicKey);
+
+    const mintData = await program.account.tokenMint.fetch(mintAddress);
+    assert.equal(mintData.authority.toString(), newAuthority.publicKey.toString());
+
+    // The previous authority can no longer mint.
+    try {
+      await mintTokens(program, mintAddress, tokenAccount, authority.publicKey, 100);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidMintAuthority");
+      console.log("Mint by previous authority failed.");
+    }
+  });
+
+  it("Should not mint after the mint authority is revoked", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const user = Keypair.generate();
+    const tokenAccount = await createTokenAccount(program, mintAddress, user.publicKey, authority.publicKey);
+
+    await mintTokens(program, mintAddress, tokenAccount, authority.publicKey, 100);
+    await revokeMintAuthority(program, mintAddress, authority.publicKey);
+
+    const mintData = await program.account.tokenMint.fetch(mintAddress);
+    assert.isNull(mintData.authority);
+    assert.equal(mintData.totalSupply.toNumber(), 100);
+
+    try {
+      await mintTokens(program, mintAddress, tokenAccount, authority.publicKey, 100);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "MintAuthorityRevoked");
+      console.log("Mint after revocation failed.");
+    }
+  });
+
+  it("Should hand over the mint authority once accepted", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const newAuthority = Keypair.generate();
+
+    await proposeMintAuthority(program, mintAddress, authority.publicKey, newAuthority.publicKey);
+
+    // Proposing alone does not change the authority.
+    let mintData = await program.account.tokenMint.fetch(mintAddress);
+    assert.equal(mintData.authority.toString(), authority.publicKey.toString());
+    assert.equal(mintData.pendingAuthority.toString(), newAuthority.publicKey.toString());
+
+    await acceptMintAuthority(program, mintAddress, newAuthority);
+
+    mintData = await program.account.tokenMint.fetch(mintAddress);
+    assert.equal(mintData.authority.toString(), newAuthority.publicKey.toString());
+    assert.isNull(mintData.pendingAuthority);
+  });
+
+  it("Should not accept the mint authority from a non-pending signer", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const newAuthority = Keypair.generate();
+    const impostor = Keypair.generate();
+
+    await proposeMintAuthority(program, mintAddress, authority.publicKey, newAuthority.publicKey);
+
+    try {
+      await acceptMintAuthority(program, mintAddress, impostor);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidPendingAuthority");
+      console.log("Acceptance by non-pending signer failed.");
+    }
+
+    // Once cancelled, even the proposed key can no longer accept.
+    await cancelMintAuthorityTransfer(program, mintAddress, authority.publicKey);
+
+    try {
+      await acceptMintAuthority(program, mintAddress, newAuthority);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "NoPendingAuthority");
+      console.log("Acceptance after cancellation failed.");
+    }
+  });
+
+  it("Should not transfer or burn from a frozen account", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals,
+      { freezeAuthority: authority.publicKey }
+    );
+    const user1 = Keypair.generate();
+    const user2 = Keypair.generate();
+    const user1TokenAccount = await createTokenAccount(program, mintAddress, user1.publicKey, authority.publicKey);
+    const user2TokenAccount = await createTokenAccount(program, mintAddress, user2.publicKey, authority.publicKey);
+
+    await mintTokens(program, mintAddress, user1TokenAccount, authority.publicKey, 1000);
+    await freezeAccount(program, mintAddress, user1TokenAccount, authority.publicKey);
+
+    try {
+      await transferTokens(program, user1TokenAccount, user2TokenAccount, user1, 100);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "AccountFrozen");
+      console.log("Transfer from frozen account failed.");
+    }
+
+    try {
+      await burnTokens(program, mintAddress, user1TokenAccount, user1, 100);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "AccountFrozen");
+      console.log("Burn from frozen account failed.");
+    }
+
+    // Thawing restores normal operation.
+    await thawAccount(program, mintAddress, user1TokenAccount, authority.publicKey);
+    await transferTokens(program, user1TokenAccount, user2TokenAccount, user1, 100);
+
+    const user2Data = await program.account.tokenAccount.fetch(user2TokenAccount);
+    assert.equal(user2Data.amount.toNumber(), 100);
+  });
+
+  it("Should not freeze without a freeze authority", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const user = Keypair.generate();
+    const tokenAccount = await createTokenAccount(program, mintAddress, user.publicKey, authority.publicKey);
+
+    try {
+      await freezeAccount(program, mintAddress, tokenAccount, authority.publicKey);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "NoFreezeAuthority");
+      console.log("Freeze without freeze authority failed.");
+    }
+  });
+
+  it("Should let a delegate transfer and burn within its allowance", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const owner = Keypair.generate();
+    const recipient = Keypair.generate();
+    const delegate = Keypair.generate();
+    const ownerTokenAccount = await createTokenAccount(program, mintAddress, owner.publicKey, authority.publicKey);
+    const recipientTokenAccount = await createTokenAccount(program, mintAddress, recipient.publicKey, authority.publicKey);
+
+    await mintTokens(program, mintAddress, ownerTokenAccount, authority.publicKey, 1000);
+    await approve(program, ownerTokenAccount, owner, delegate.publicKey, 500);
+
+    await transferTokens(program, ownerTokenAccount, recipientTokenAccount, delegate, 300);
+    await burnTokens(program, mintAddress, ownerTokenAccount, delegate, 100);
+
+    const ownerData = await program.account.tokenAccount.fetch(ownerTokenAccount);
+    assert.equal(ownerData.amount.toNumber(), 600);
+    assert.equal(ownerData.delegate.toString(), delegate.publicKey.toString());
+    assert.equal(ownerData.delegatedAmount.toNumber(), 100);
+
+    // The remaining allowance caps what the delegate can move.
+    try {
+      await transferTokens(program, ownerTokenAccount, recipientTokenAccount, delegate, 101);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "InsufficientAllowance");
+      console.log("Transfer above allowance failed.");
+    }
+  });
+
+  it("Should not let a revoked delegate transfer", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const owner = Keypair.generate();
+    const recipient = Keypair.generate();
+    const delegate = Keypair.generate();
+    const ownerTokenAccount = await createTokenAccount(program, mintAddress, owner.publicKey, authority.publicKey);
+    const recipientTokenAccount = await createTokenAccount(program, mintAddress, recipient.publicKey, authority.publicKey);
+
+    await mintTokens(program, mintAddress, ownerTokenAccount, authority.publicKey, 1000);
+    await approve(program, ownerTokenAccount, owner, delegate.publicKey, 500);
+    await revoke(program, ownerTokenAccount, owner);
+
+    try {
+      await transferTokens(program, ownerTokenAccount, recipientTokenAccount, delegate, 100);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidTokenAuthority");
+      console.log("Transfer by revoked delegate failed.");
+    }
+  });
+
+  it("Should approve a delegate with a permit signed by the owner", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const owner = Keypair.generate();
+    const recipient = Keypair.generate();
+    const spender = Keypair.generate();
+    const ownerTokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      owner.publicKey,
+      authority.publicKey
+    );
+    const recipientTokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      recipient.publicKey,
+      authority.publicKey
+    );
+    await mintTokens(
+      program,
+      mintAddress,
+      ownerTokenAccount,
+      authority.publicKey,
+      1000
+    );
+
+    // The owner never signs a transaction; the provider wallet submits for them.
+    const deadline = Math.floor(Date.now() / 1000) + 3600;
+    await permit(
+      program,
+      mintAddress,
+      ownerTokenAccount,
+      owner,
+      spender.publicKey,
+      400,
+      1,
+      deadline,
+      authority.publicKey
+    );
+    await transferTokens(
+      program,
+      ownerTokenAccount,
+      recipientTokenAccount,
+      spender,
+      250
+    );
+
+    const ownerData = await program.account.tokenAccount.fetch(
+      ownerTokenAccount
+    );
+    assert.equal(ownerData.amount.toNumber(), 750);
+    assert.equal(ownerData.delegate.toString(), spender.publicKey.toString());
+    assert.equal(ownerData.delegatedAmount.toNumber(), 150);
+
+    try {
+      await permit(
+        program,
+        mintAddress,
+        ownerTokenAccount,
+        owner,
+        spender.publicKey,
+        400,
+        1,
+        deadline,
+        authority.publicKey
+      );
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "already in use");
+      console.log("Replaying a permit failed.");
+    }
+  });
+
+  it("Should not accept a permit whose nonce the owner cancelled", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const owner = Keypair.generate();
+    const spender = Keypair.generate();
+    const ownerTokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      owner.publicKey,
+      authority.publicKey
+    );
+
+    await cancelPermit(program, mintAddress, owner, 1, authority.publicKey);
+
+    try {
+      const deadline = Math.floor(Date.now() / 1000) + 3600;
+      await permit(
+        program,
+        mintAddress,
+        ownerTokenAccount,
+        owner,
+        spender.publicKey,
+        400,
+        1,
+        deadline,
+        authority.publicKey
+      );
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "already in use");
+      console.log("Submitting a cancelled permit failed.");
+    }
+  });
+
+  it("Should not accept an expired or wrongly signed permit", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const owner = Keypair.generate();
+    const spender = Keypair.generate();
+    const ownerTokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      owner.publicKey,
+      authority.publicKey
+    );
+
+    try {
+      const deadline = Math.floor(Date.now() / 1000) - 60;
+      await permit(
+        program,
+        mintAddress,
+        ownerTokenAccount,
+        owner,
+        spender.publicKey,
+        400,
+        1,
+        deadline,
+        authority.publicKey
+      );
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "PermitExpired");
+      console.log("Submitting an expired permit failed.");
+    }
+
+    // A permit signed by the spender instead of the owner.
+    try {
+      const deadline = Math.floor(Date.now() / 1000) + 3600;
+      await permit(
+        program,
+        mintAddress,
+        ownerTokenAccount,
+        owner,
+        spender.publicKey,
+        400,
+        1,
+        deadline,
+        authority.publicKey,
+        spender
+      );
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidPermitSignature");
+      console.log("Submitting a permit not signed by the owner failed.");
+    }
+  });
+
+  it("Should close an empty token account and reclaim rent", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const user = Keypair.generate();
+    const destination = Keypair.generate();
+    const tokenAccount = await createTokenAccount(program, mintAddress, user.publicKey, authority.publicKey);
+    const rent = await program.provider.connection.getBalance(tokenAccount);
+
+    await closeTokenAccount(program, tokenAccount, user, destination.publicKey);
+
+    const accountInfo = await program.provider.connection.getAccountInfo(tokenAccount);
+    assert.isNull(accountInfo);
+    const destinationBalance = await program.provider.connection.getBalance(destination.publicKey);
+    assert.equal(destinationBalance, rent);
+  });
+
+  it("Should not close a token account holding tokens", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const user = Keypair.generate();
+    const tokenAccount = await createTokenAccount(program, mintAddress, user.publicKey, authority.publicKey);
+
+    await mintTokens(program, mintAddress, tokenAccount, authority.publicKey, 100);
+
+    try {
+      await closeTokenAccount(program, tokenAccount, user, user.publicKey);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "NonZeroBalance");
+      console.log("Close of non-empty account failed.");
+    }
+  });
+
+  it("Should harvest withheld fees onto the mint when closing an account", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals,
+      { feeAuthority: authority.publicKey }
+    );
+    const user1 = Keypair.generate();
+    const user2 = Keypair.generate();
+    const user1TokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      user1.publicKey,
+      authority.publicKey
+    );
+    const user2TokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      user2.publicKey,
+      authority.publicKey
+    );
+
+    // 1% fee: user2 receives 990 and 10 are withheld on its account.
+    await setTransferFee(program, mintAddress, authority.publicKey, 100, 100);
+    await mintTokens(
+      program,
+      mintAddress,
+      user1TokenAccount,
+      authority.publicKey,
+      1000
+    );
+    await transferTokens(
+      program,
+      user1TokenAccount,
+      user2TokenAccount,
+      user1,
+      1000
+    );
+    await burnTokens(program, mintAddress, user2TokenAccount, user2, 990);
+
+    await closeTokenAccount(program, user2TokenAccount, user2, user2.publicKey);
+
+    assert.isNull(
+      await program.provider.connection.getAccountInfo(user2TokenAccount)
+    );
+    const mintData = await program.account.tokenMint.fetch(mintAddress);
+    assert.equal(mintData.withheldAmount.toNumber(), 10);
+  });
+
+  it("Should not mint beyond the maximum supply", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals,
+      { maxSupply: 1000 }
+    );
+    const user = Keypair.generate();
+    const tokenAccount = await createTokenAccount(program, mintAddress, user.publicKey, authority.publicKey);
+
+    // Minting exactly up to the cap is allowed.
+    await mintTokens(program, mintAddress, tokenAccount, authority.publicKey, 1000);
+
+    try {
+      await mintTokens(program, mintAddress, tokenAccount, authority.publicKey, 1);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "MaxSupplyExceeded");
+      console.log("Mint beyond max supply failed.");
+    }
+
+    const mintData = await program.account.tokenMint.fetch(mintAddress);
+    assert.equal(mintData.maxSupply.toNumber(), 1000);
+    assert.equal(mintData.totalSupply.toNumber(), 1000);
+  });
+
+  it("Should block minting, transfers and burns while paused", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals,
+      { pauseAuthority: authority.publicKey }
+    );
+    const user1 = Keypair.generate();
+    const user2 = Keypair.generate();
+    const user1TokenAccount = await createTokenAccount(program, mintAddress, user1.publicKey, authority.publicKey);
+    const user2TokenAccount = await createTokenAccount(program, mintAddress, user2.publicKey, authority.publicKey);
+
+    await mintTokens(program, mintAddress, user1TokenAccount, authority.publicKey, 1000);
+    await pause(program, mintAddress, authority.publicKey);
+
+    const attempts = [
+      () => mintTokens(program, mintAddress, user1TokenAccount, authority.publicKey, 100),
+      () => transferTokens(program, user1TokenAccount, user2TokenAccount, user1, 100),
+      () => burnTokens(program, mintAddress, user1TokenAccount, user1, 100),
+    ];
+    for (const attempt of attempts) {
+      try {
+        await attempt();
+        assert.fail("Expected error was not thrown");
+      } catch (error) {
+        assert.include(error.toString(), "MintPaused");
+      }
+    }
+
+    await unpause(program, mintAddress, authority.publicKey);
+    await transferTokens(program, user1TokenAccount, user2TokenAccount, user1, 100);
+
+    const user2Data = await program.account.tokenAccount.fetch(user2TokenAccount);
+    assert.equal(user2Data.amount.toNumber(), 100);
+  });
+
+  it("Should emit an event when minting tokens", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const user = Keypair.generate();
+    const tokenAccount = await createTokenAccount(program, mintAddress, user.publicKey, authority.publicKey);
+
+    let event = null;
+    const listener = program.addEventListener("tokensMinted", (e) => {
+      event = e;
+    });
+
+    await mintTokens(program, mintAddress, tokenAccount, authority.publicKey, 500);
+
+    // Give the websocket subscription time to deliver the log.
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+
+    assert.isNotNull(event);
+    assert.equal(event.mint.toString(), mintAddress.toString());
+    assert.equal(event.tokenAccount.toString(), tokenAccount.toString());
+    assert.equal(event.amount.toNumber(), 500);
+    assert.equal(event.totalSupply.toNumber(), 500);
+  });
+
+  it("Should store and update token metadata", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const metadataAddress = await initializeMetadata(
+      program,
+      mintAddress,
+      authority.publicKey,
+      "Test Token",
+      "TEST",
+      "https://example.com/test.json"
+    );
+
+    await updateMetadata(program, metadataAddress, authority.payer, { symbol: "TST" });
+    await setMetadataField(program, metadataAddress, authority.publicKey, "website", "example.com");
+    await setMetadataField(program, metadataAddress, authority.publicKey, "website", "example.org");
+    await setMetadataField(program, metadataAddress, authority.publicKey, "twitter", "@test");
+    await removeMetadataField(program, metadataAddress, authority.publicKey, "twitter");
+
+    const metadata = await program.account.tokenMetadata.fetch(metadataAddress);
+    assert.equal(metadata.mint.toString(), mintAddress.toString());
+    assert.equal(metadata.updateAuthority.toString(), authority.publicKey.toString());
+    assert.equal(metadata.name, "Test Token");
+    assert.equal(metadata.symbol, "TST");
+    assert.equal(metadata.uri, "https://example.com/test.json");
+    assert.deepEqual(metadata.additionalMetadata, [{ key: "website", value: "example.org" }]);
+  });
+
+  it("Should not let a non-authority update metadata", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const metadataAddress = await initializeMetadata(
+      program,
+      mintAddress,
+      authority.publicKey,
+      "Test Token",
+      "TEST",
+      "https://example.com/test.json"
+    );
+    const attacker = Keypair.generate();
+
+    try {
+      await updateMetadata(program, metadataAddress, attacker, { name: "Fake Token" });
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidUpdateAuthority");
+      console.log("Unauthorized metadata update failed.");
+    }
+  });
+
+  it("Should reject metadata changes once immutable", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const metadataAddress = await initializeMetadata(
+      program,
+      mintAddress,
+      authority.publicKey,
+      "Test Token",
+      "TEST",
+      "https://example.com/test.json"
+    );
+
+    await setMetadataUpdateAuthority(program, metadataAddress, authority.publicKey, null);
+
+    try {
+      await updateMetadata(program, metadataAddress, authority.payer, { name: "Renamed" });
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "MetadataImmutable");
+      console.log("Update of immutable metadata failed.");
+    }
+  });
+
+  it("Should reject metadata that is too long", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+
+    try {
+      await initializeMetadata(
+        program,
+        mintAddress,
+        authority.publicKey,
+        "x".repeat(33),
+        "TEST",
+        "https://example.com/test.json"
+      );
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "MetadataTooLong");
+      console.log("Oversized metadata failed.");
+    }
+  });
+
+  it("Should withhold transfer fees and let the fee authority collect them", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals,
+      { feeAuthority: authority.publicKey }
+    );
+    const user1 = Keypair.generate();
+    const user2 = Keypair.generate();
+    const user1TokenAccount = await createTokenAccount(program, mintAddress, user1.publicKey, authority.publicKey);
+    const user2TokenAccount = await createTokenAccount(program, mintAddress, user2.publicKey, authority.publicKey);
+    const collectorTokenAccount = await createTokenAccount(program, mintAddress, authority.publicKey, authority.publicKey);
+
+    // 1% fee, capped at 5 tokens per transfer.
+    await setTransferFee(program, mintAddress, authority.publicKey, 100, 5);
+    await mintTokens(program, mintAddress, user1TokenAccount, authority.publicKey, 10000);
+
+    await transferTokens(program, user1TokenAccount, user2TokenAccount, user1, 250);
+    await transferTokens(program, user1TokenAccount, user2TokenAccount, user1, 1000);
+
+    let user2Data = await program.account.tokenAccount.fetch(user2TokenAccount);
+    assert.equal(user2Data.amount.toNumber(), (250 - 3) + (1000 - 5));
+    assert.equal(user2Data.withheldAmount.toNumber(), 3 + 5);
+
+    await harvestWithheld(program, mintAddress, user2TokenAccount, authority.publicKey);
+    await withdrawWithheld(program, mintAddress, collectorTokenAccount, authority.publicKey);
+
+    user2Data = await program.account.tokenAccount.fetch(user2TokenAccount);
+    const collectorData = await program.account.tokenAccount.fetch(collectorTokenAccount);
+    const mintData = await program.account.tokenMint.fetch(mintAddress);
+    assert.equal(user2Data.withheldAmount.toNumber(), 0);
+    assert.equal(collectorData.amount.toNumber(), 8);
+    assert.equal(mintData.withheldAmount.toNumber(), 0);
+    assert.equal(mintData.totalSupply.toNumber(), 10000);
+  });
+
+  it("Should not let a non-fee-authority set or collect fees", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals,
+      { feeAuthority: Keypair.generate().publicKey }
+    );
+    const tokenAccount = await createTokenAccount(program, mintAddress, authority.publicKey, authority.publicKey);
+
+    const attempts = [
+      () => setTransferFee(program, mintAddress, authority.publicKey, 100, 5),
+      () => harvestWithheld(program, mintAddress, tokenAccount, authority.publicKey),
+      () => withdrawWithheld(program, mintAddress, tokenAccount, authority.publicKey),
+    ];
+    for (const attempt of attempts) {
+      try {
+        await attempt();
+        assert.fail("Expected error was not thrown");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidFeeAuthority");
+      }
+    }
+  });
+
+  it("Should not set a transfer fee above 100%", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals,
+      { feeAuthority: authority.publicKey }
+    );
+
+    try {
+      await setTransferFee(program, mintAddress, authority.publicKey, 10001, 5);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidTransferFee");
+      console.log("Transfer fee above 100% failed.");
+    }
+  });
+
+  it("Should mint and burn but not transfer non-transferable tokens", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals,
+      { nonTransferable: true }
+    );
+    const user1 = Keypair.generate();
+    const user2 = Keypair.generate();
+    const user1TokenAccount = await createTokenAccount(program, mintAddress, user1.publicKey, authority.publicKey);
+    const user2TokenAccount = await createTokenAccount(program, mintAddress, user2.publicKey, authority.publicKey);
+
+    await mintTokens(program, mintAddress, user1TokenAccount, authority.publicKey, 1);
+
+    try {
+      await transferTokens(program, user1TokenAccount, user2TokenAccount, user1, 1);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "NonTransferable");
+      console.log("Transfer of non-transferable token failed.");
+    }
+
+    await burnTokens(program, mintAddress, user1TokenAccount, user1, 1);
+
+    const user1Data = await program.account.tokenAccount.fetch(user1TokenAccount);
+    assert.equal(user1Data.amount.toNumber(), 0);
+  });
+
+  it("Should let the permanent delegate claw back tokens without the owner", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals,
+      { permanentDelegate: authority.publicKey }
+    );
+    const holder = Keypair.generate();
+    const treasury = Keypair.generate();
+    const holderTokenAccount = await createTokenAccount(program, mintAddress, holder.publicKey, authority.publicKey);
+    const treasuryTokenAccount = await createTokenAccount(program, mintAddress, treasury.publicKey, authority.publicKey);
+
+    await mintTokens(program, mintAddress, holderTokenAccount, authority.publicKey, 1000);
+
+    let event = null;
+    const listener = program.addEventListener("clawedBack", (e) => {
+      event = e;
+    });
+
+    await transferTokens(program, holderTokenAccount, treasuryTokenAccount, authority.payer, 600);
+    await burnTokens(program, mintAddress, holderTokenAccount, authority.payer, 400);
+
+    // Give the websocket subscription time to deliver the log.
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+
+    const holderData = await program.account.tokenAccount.fetch(holderTokenAccount);
+    const treasuryData = await program.account.tokenAccount.fetch(treasuryTokenAccount);
+    const mintData = await program.account.tokenMint.fetch(mintAddress);
+    assert.equal(holderData.amount.toNumber(), 0);
+    assert.equal(treasuryData.amount.toNumber(), 600);
+    assert.equal(mintData.totalSupply.toNumber(), 600);
+
+    assert.isNotNull(event);
+    assert.equal(event.tokenAccount.toString(), holderTokenAccount.toString());
+    assert.equal(event.permanentDelegate.toString(), authority.publicKey.toString());
+    assert.isNull(event.destination);
+    assert.equal(event.amount.toNumber(), 400);
+  });
+
+  it("Should not let anyone else spend without the owner", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals,
+      { permanentDelegate: Keypair.generate().publicKey }
+    );
+    const holder = Keypair.generate();
+    const holderTokenAccount = await createTokenAccount(program, mintAddress, holder.publicKey, authority.publicKey);
+
+    await mintTokens(program, mintAddress, holderTokenAccount, authority.publicKey, 1000);
+
+    try {
+      await burnTokens(program, mintAddress, holderTokenAccount, authority.payer, 100);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidTokenAuthority");
+      console.log("Burn by non-delegate failed.");
+    }
+  });
+
+  it("Should create frozen token accounts until the freeze authority approves them", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals,
+      { freezeAuthority: authority.publicKey, defaultFrozen: true }
+    );
+    const user = Keypair.generate();
+    const tokenAccount = await createTokenAccount(program, mintAddress, user.publicKey, authority.publicKey);
+
+    let tokenData = await program.account.tokenAccount.fetch(tokenAccount);
+    assert.deepEqual(tokenData.state, { frozen: {} });
+
+    try {
+      await mintTokens(program, mintAddress, tokenAccount, authority.publicKey, 100);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "AccountFrozen");
+      console.log("Mint to unapproved account failed.");
+    }
+
+    await thawAccount(program, mintAddress, tokenAccount, authority.publicKey);
+    await mintTokens(program, mintAddress, tokenAccount, authority.publicKey, 100);
+
+    tokenData = await program.account.tokenAccount.fetch(tokenAccount);
+    assert.deepEqual(tokenData.state, { initialized: {} });
+    assert.equal(tokenData.amount.toNumber(), 100);
+  });
+
+  it("Should not default accounts to frozen without a freeze authority", async () => {
+    try {
+      await initializeMint(program, authority.publicKey, decimals, { defaultFrozen: true });
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "NoFreezeAuthority");
+      console.log("Default-frozen mint without freeze authority failed.");
+    }
+  });
+
+  it("Should block denylisted wallets on both sides until removed", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals,
+      { freezeAuthority: authority.publicKey }
+    );
+    const user1 = Keypair.generate();
+    const user2 = Keypair.generate();
+    const user1TokenAccount = await createTokenAccount(program, mintAddress, user1.publicKey, authority.publicKey);
+    const user2TokenAccount = await createTokenAccount(program, mintAddress, user2.publicKey, authority.publicKey);
+
+    await mintTokens(program, mintAddress, user1TokenAccount, authority.publicKey, 1000);
+    await mintTokens(program, mintAddress, user2TokenAccount, authority.publicKey, 1000);
+    await addToDenylist(program, mintAddress, user2.publicKey, authority.publicKey);
+
+    const attempts = [
+      () => mintTokens(program, mintAddress, user2TokenAccount, authority.publicKey, 100),
+      () => transferTokens(program, user1TokenAccount, user2TokenAccount, user1, 100),
+      () => transferTokens(program, user2TokenAccount, user1TokenAccount, user2, 100),
+      () => burnTokens(program, mintAddress, user2TokenAccount, user2, 100),
+    ];
+    for (const attempt of attempts) {
+      try {
+        await attempt();
+        assert.fail("Expected error was not thrown");
+      } catch (error) {
+        assert.include(error.toString(), "WalletDenied");
+      }
+    }
+
+    await removeFromDenylist(program, mintAddress, user2.publicKey, authority.publicKey);
+    await transferTokens(program, user2TokenAccount, user1TokenAccount, user2, 100);
+
+    const user1Data = await program.account.tokenAccount.fetch(user1TokenAccount);
+    assert.equal(user1Data.amount.toNumber(), 1100);
+  });
+
+  it("Should not let a non-freeze-authority manage the denylist", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals,
+      { freezeAuthority: Keypair.generate().publicKey }
+    );
+
+    try {
+      await addToDenylist(program, mintAddress, Keypair.generate().publicKey, authority.publicKey);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidFreezeAuthority");
+      console.log("Unauthorized denylist update failed.");
+    }
+  });
+
+  it("Should mint to many recipients in one instruction", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const recipients = [];
+    for (const amount of [100, 200, 300]) {
+      const owner = Keypair.generate();
+      const tokenAccount = await createTokenAccount(program, mintAddress, owner.publicKey, authority.publicKey);
+      recipients.push({ tokenAccount, amount });
+    }
+    // A recipient listed twice is credited both amounts.
+    recipients.push({ tokenAccount: recipients[0].tokenAccount, amount: 50 });
+
+    await mintToMany(program, mintAddress, authority.publicKey, recipients);
+
+    const balances = await Promise.all(
+      recipients.slice(0, 3).map(({ tokenAccount }) => program.account.tokenAccount.fetch(tokenAccount))
+    );
+    assert.deepEqual(balances.map((b) => b.amount.toNumber()), [150, 200, 300]);
+
+    const mintData = await program.account.tokenMint.fetch(mintAddress);
+    assert.equal(mintData.totalSupply.toNumber(), 650);
+  });
+
+  it("Should not mint to many when a recipient belongs to another mint", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const otherMint = await initializeMint(program, authority.publicKey, decimals);
+    const tokenAccount = await createTokenAccount(program, mintAddress, Keypair.generate().publicKey, authority.publicKey);
+    const otherTokenAccount = await createTokenAccount(program, otherMint, Keypair.generate().publicKey, authority.publicKey);
+
+    try {
+      await mintToMany(program, mintAddress, authority.publicKey, [
+        { tokenAccount, amount: 100 },
+        { tokenAccount: otherTokenAccount, amount: 100 },
+      ]);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "MintMismatch");
+      console.log("Batch mint to foreign token account failed.");
+    }
+
+    const mintData = await program.account.tokenMint.fetch(mintAddress);
+    assert.equal(mintData.totalSupply.toNumber(), 0);
+  });
+
+  it("Should pay many recipients from one account in one instruction", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const treasury = Keypair.generate();
+    const treasuryTokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      treasury.publicKey,
+      authority.publicKey
+    );
+    await mintTokens(
+      program,
+      mintAddress,
+      treasuryTokenAccount,
+      authority.publicKey,
+      1000
+    );
+
+    const recipients = [];
+    for (let i = 0; i < 22; i++) {
+      const owner = Keypair.generate();
+      const tokenAccount = await createTokenAccount(
+        program,
+        mintAddress,
+        owner.publicKey,
+        authority.publicKey
+      );
+      recipients.push({ tokenAccount, amount: i + 1 });
+    }
+
+    // Two accounts per recipient: with the treasury signing next to the fee payer,
+    // 11 payouts is the most a transaction can hold.
+    await transferMany(
+      program,
+      treasuryTokenAccount,
+      treasury,
+      recipients.slice(0, 11)
+    );
+    await transferMany(
+      program,
+      treasuryTokenAccount,
+      treasury,
+      recipients.slice(11)
+    );
+
+    const treasuryData = await program.account.tokenAccount.fetch(
+      treasuryTokenAccount
+    );
+    assert.equal(treasuryData.amount.toNumber(), 1000 - 253);
+
+    const lastData = await program.account.tokenAccount.fetch(
+      recipients[21].tokenAccount
+    );
+    assert.equal(lastData.amount.toNumber(), 22);
+  });
+
+  it("Should not pay many recipients beyond the sender's balance", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const treasury = Keypair.generate();
+    const treasuryTokenAccount = await createTokenAccount(program, mintAddress, treasury.publicKey, authority.publicKey);
+    const recipient = await createTokenAccount(program, mintAddress, Keypair.generate().publicKey, authority.publicKey);
+    await mintTokens(program, mintAddress, treasuryTokenAccount, authority.publicKey, 100);
+
+    try {
+      await transferMany(program, treasuryTokenAccount, treasury, [
+        { tokenAccount: recipient, amount: 60 },
+        { tokenAccount: recipient, amount: 60 },
+      ]);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "InsufficientFunds");
+      console.log("Batch transfer beyond balance failed.");
+    }
+  });
+
+  it("Should not pay the sender itself in a batch transfer", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const treasury = Keypair.generate();
+    const treasuryTokenAccount = await createTokenAccount(program, mintAddress, treasury.publicKey, authority.publicKey);
+    await mintTokens(program, mintAddress, treasuryTokenAccount, authority.publicKey, 100);
+
+    try {
+      await transferMany(program, treasuryTokenAccount, treasury, [
+        { tokenAccount: treasuryTokenAccount, amount: 10 },
+      ]);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "SelfTransfer");
+      console.log("Batch transfer to self failed.");
+    }
+  });
+
+  it("Should mint with a multisig mint authority", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+    const multisig = await createMultisig(
+      program,
+      2,
+      members.map((member) => member.publicKey),
+      authority.publicKey
+    );
+    await setMintAuthority(program, mintAddress, authority.publicKey, multisig);
+
+    const user = Keypair.generate();
+    const tokenAccount = await createTokenAccount(program, mintAddress, user.publicKey, authority.publicKey);
+    await mintTokens(program, mintAddress, tokenAccount, multisig, 100, [members[0], members[2]]);
+
+    const accountData = await program.account.tokenAccount.fetch(tokenAccount);
+    assert.equal(accountData.amount.toNumber(), 100);
+
+    try {
+      await mintTokens(program, mintAddress, tokenAccount, multisig, 100, [members[1]]);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "NotEnoughSigners");
+      console.log("Minting below the multisig threshold failed.");
+    }
+  });
+
+  it("Should create and update metadata with a multisig mint authority", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const members = [
+      Keypair.generate(),
+      Keypair.generate(),
+      Keypair.generate(),
+    ];
+    const multisig = await createMultisig(
+      program,
+      2,
+      members.map((member) => member.publicKey),
+      authority.publicKey
+    );
+    await setMintAuthority(program, mintAddress, authority.publicKey, multisig);
+
+    try {
+      await initializeMetadata(
+        program,
+        mintAddress,
+        multisig,
+        "Treasury Token",
+        "TRS",
+        "https://example.com/trs.json",
+        [members[0]]
+      );
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "NotEnoughSigners");
+      console.log("Creating metadata below the multisig threshold failed.");
+    }
+
+    const metadataAddress = await initializeMetadata(
+      program,
+      mintAddress,
+      multisig,
+      "Treasury Token",
+      "TRS",
+      "https://example.com/trs.json",
+      [members[0], members[1]]
+    );
+    await setMetadataField(
+      program,
+      metadataAddress,
+      multisig,
+      "issuer",
+      "DAO",
+      [members[1], members[2]]
+    );
+
+    const metadata = await program.account.tokenMetadata.fetch(metadataAddress);
+    assert.equal(metadata.updateAuthority.toString(), multisig.toString());
+    assert.equal(metadata.additionalMetadata[0].value, "DAO");
+  });
+
+  it("Should not create a multisig with a threshold above its signer count", async () => {
+    try {
+      await createMultisig(
+        program,
+        3,
+        [Keypair.generate().publicKey, Keypair.generate().publicKey],
+        authority.publicKey
+      );
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidMultisig");
+      console.log("Multisig with unreachable threshold failed.");
+    }
+  });
+
+  it("Should mint through the queue once the delay has passed", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const user = Keypair.generate();
+    const tokenAccount = await createTokenAccount(program, mintAddress, user.publicKey, authority.publicKey);
+    await setMintDelay(program, mintAddress, authority.publicKey, 2);
+
+    try {
+      await mintTokens(program, mintAddress, tokenAccount, authority.publicKey, 100);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "MintTimelocked");
+      console.log("Direct mint on a timelocked mint failed.");
+    }
+
+    const pendingMint = await queueMint(program, mintAddress, tokenAccount, authority.publicKey, 100);
+
+    try {
+      await executeMint(program, pendingMint, authority.publicKey);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "MintNotReady");
+      console.log("Executing a queued mint early failed.");
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, 4000));
+    await executeMint(program, pendingMint, authority.publicKey);
+
+    const accountData = await program.account.tokenAccount.fetch(tokenAccount);
+    assert.equal(accountData.amount.toNumber(), 100);
+    assert.isNull(await program.account.pendingMint.fetchNullable(pendingMint));
+  });
+
+  it("Should cancel a queued mint", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const user = Keypair.generate();
+    const tokenAccount = await createTokenAccount(program, mintAddress, user.publicKey, authority.publicKey);
+    await setMintDelay(program, mintAddress, authority.publicKey, 3600);
+
+    const pendingMint = await queueMint(program, mintAddress, tokenAccount, authority.publicKey, 100);
+    await cancelMint(program, pendingMint, authority.publicKey);
+
+    assert.isNull(await program.account.pendingMint.fetchNullable(pendingMint));
+    const mintData = await program.account.tokenMint.fetch(mintAddress);
+    assert.equal(mintData.totalSupply.toNumber(), 0);
+  });
+
+  it("Should not shorten the mint delay before the current delay has passed", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    await setMintDelay(program, mintAddress, authority.publicKey, 3600);
+    await setMintDelay(program, mintAddress, authority.publicKey, 60);
+
+    const mintData = await program.account.tokenMint.fetch(mintAddress);
+    assert.equal(mintData.mintDelay.toNumber(), 3600);
+    assert.equal(mintData.pendingMintDelay.toNumber(), 60);
+
+    try {
+      await applyMintDelay(program, mintAddress, authority.publicKey);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "MintDelayChangeNotReady");
+      console.log("Shortening the mint delay early failed.");
+    }
+  });
+
+  it("Should lift the mint timelock once the delay has passed", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const user = Keypair.generate();
+    const tokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      user.publicKey,
+      authority.publicKey
+    );
+    await setMintDelay(program, mintAddress, authority.publicKey, 2);
+    await setMintDelay(program, mintAddress, authority.publicKey, 0);
+
+    await new Promise((resolve) => setTimeout(resolve, 4000));
+    await applyMintDelay(program, mintAddress, authority.publicKey);
+    await mintTokens(
+      program,
+      mintAddress,
+      tokenAccount,
+      authority.publicKey,
+      100
+    );
+
+    const accountData = await program.account.tokenAccount.fetch(tokenAccount);
+    assert.equal(accountData.amount.toNumber(), 100);
+    const mintData = await program.account.tokenMint.fetch(mintAddress);
+    assert.equal(mintData.mintDelay.toNumber(), 0);
+    assert.isNull(mintData.pendingMintDelay);
+  });
+
+  it("Should not mint beyond the rate limit of the window", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const user = Keypair.generate();
+    const tokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      user.publicKey,
+      authority.publicKey
+    );
+    await setMintRateLimit(
+      program,
+      mintAddress,
+      authority.publicKey,
+      100,
+      3600
+    );
+
+    await mintTokens(
+      program,
+      mintAddress,
+      tokenAccount,
+      authority.publicKey,
+      60
+    );
+
+    try {
+      await mintTokens(
+        program,
+        mintAddress,
+        tokenAccount,
+        authority.publicKey,
+        50
+      );
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "MintRateLimitExceeded");
+      console.log("Minting beyond the window's capacity failed.");
+    }
+
+    await mintTokens(
+      program,
+      mintAddress,
+      tokenAccount,
+      authority.publicKey,
+      40
+    );
+
+    const mintData = await program.account.tokenMint.fetch(mintAddress);
+    assert.equal(mintData.rateLimitUsed.toNumber(), 100);
+    assert.equal(mintData.totalSupply.toNumber(), 100);
+  });
+
+  it("Should free up rate limit capacity gradually", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const user = Keypair.generate();
+    const tokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      user.publicKey,
+      authority.publicKey
+    );
+    // 100 tokens per 20 seconds frees up 5 tokens per second.
+    await setMintRateLimit(program, mintAddress, authority.publicKey, 100, 20);
+
+    await mintTokens(
+      program,
+      mintAddress,
+      tokenAccount,
+      authority.publicKey,
+      100
+    );
+    await new Promise((resolve) => setTimeout(resolve, 5000));
+
+    try {
+      await mintTokens(
+        program,
+        mintAddress,
+        tokenAccount,
+        authority.publicKey,
+        100
+      );
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "MintRateLimitExceeded");
+      console.log("Minting the full limit again within the window failed.");
+    }
+
+    await mintTokens(
+      program,
+      mintAddress,
+      tokenAccount,
+      authority.publicKey,
+      20
+    );
+
+    const mintData = await program.account.tokenMint.fetch(mintAddress);
+    assert.equal(mintData.totalSupply.toNumber(), 120);
+  });
+
+  it("Should not loosen the mint rate limit", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    await setMintRateLimit(
+      program,
+      mintAddress,
+      authority.publicKey,
+      100,
+      3600
+    );
+    await setMintRateLimit(program, mintAddress, authority.publicKey, 50, 7200);
+
+    try {
+      await setMintRateLimit(
+        program,
+        mintAddress,
+        authority.publicKey,
+        1000,
+        7200
+      );
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidMintRateLimit");
+      console.log("Raising the mint rate limit failed.");
+    }
+  });
+
+  it("Should mint as a minter within its allowance", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const minter = Keypair.generate();
+    const user = Keypair.generate();
+    const tokenAccount = await createTokenAccount(program, mintAddress, user.publicKey, authority.publicKey);
+    const minterRole = await addMinter(program, mintAddress, minter.publicKey, authority.publicKey, 100);
+
+    await mintAsMinter(program, mintAddress, tokenAccount, minter, 70);
+
+    let roleData = await program.account.minterRole.fetch(minterRole);
+    assert.equal(roleData.allowance.toNumber(), 30);
+
+    try {
+      await mintAsMinter(program, mintAddress, tokenAccount, minter, 31);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "MinterAllowanceExceeded");
+      console.log("Minting beyond the minter's allowance failed.");
+    }
+
+    await setMinterAllowance(program, mintAddress, minter.publicKey, authority.publicKey, 500);
+    await mintAsMinter(program, mintAddress, tokenAccount, minter, 500);
+
+    roleData = await program.account.minterRole.fetch(minterRole);
+    assert.equal(roleData.allowance.toNumber(), 0);
+    const accountData = await program.account.tokenAccount.fetch(tokenAccount);
+    assert.equal(accountData.amount.toNumber(), 570);
+  });
+
+  it("Should not mint as a removed minter", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const minter = Keypair.generate();
+    const user = Keypair.generate();
+    const tokenAccount = await createTokenAccount(program, mintAddress, user.publicKey, authority.publicKey);
+    await addMinter(program, mintAddress, minter.publicKey, authority.publicKey, 100);
+    await removeMinter(program, mintAddress, minter.publicKey, authority.publicKey);
+
+    try {
+      await mintAsMinter(program, mintAddress, tokenAccount, minter, 10);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "AccountNotInitialized");
+      console.log("Minting as a removed minter failed.");
+    }
+  });
+
+  it("Should release vested tokens only after the cliff", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const beneficiary = Keypair.generate();
+    const grantorTokenAccount = await createTokenAccount(program, mintAddress, authority.publicKey, authority.publicKey);
+    const beneficiaryTokenAccount = await createTokenAccount(program, mintAddress, beneficiary.publicKey, authority.publicKey);
+    await mintTokens(program, mintAddress, grantorTokenAccount, authority.publicKey, 3000);
+
+    const now = Math.floor(Date.now() / 1000);
+    const locked = await createVesting(program, mintAddress, authority.publicKey, beneficiary.publicKey, 1000, {
+      startTime: now,
+      cliffTime: now + 3600,
+      endTime: now + 7200,
+      revocable: false,
+    });
+    const vested = await createVesting(program, mintAddress, authority.publicKey, beneficiary.publicKey, 1000, {
+      startTime: now - 7200,
+      cliffTime: now - 3600,
+      endTime: now - 60,
+      revocable: false,
+    });
+
+    const grantorData = await program.account.tokenAccount.fetch(grantorTokenAccount);
+    assert.equal(grantorData.amount.toNumber(), 1000);
+
+    try {
+      await claimVested(program, locked, beneficiaryTokenAccount, beneficiary);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "NothingToClaim");
+      console.log("Claiming before the cliff failed.");
+    }
+
+    await claimVested(program, vested, beneficiaryTokenAccount, beneficiary);
+
+    const beneficiaryData = await program.account.tokenAccount.fetch(beneficiaryTokenAccount);
+    assert.equal(beneficiaryData.amount.toNumber(), 1000);
+
+    // A fully claimed schedule is closed, so it cannot be claimed again.
+    assert.isNull(await program.account.vestingAccount.fetchNullable(vested));
+    try {
+      await claimVested(program, vested, beneficiaryTokenAccount, beneficiary);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "Account does not exist");
+      console.log("Claiming a fully claimed schedule failed.");
+    }
+  });
+
+  it("Should charge the transfer fee on vested claims", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const beneficiary = Keypair.generate();
+    const grantorTokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      authority.publicKey,
+      authority.publicKey
+    );
+    const beneficiaryTokenAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      beneficiary.publicKey,
+      authority.publicKey
+    );
+    await mintTokens(
+      program,
+      mintAddress,
+      grantorTokenAccount,
+      authority.publicKey,
+      1000
+    );
+
+    const now = Math.floor(Date.now() / 1000);
+    const vesting = await createVesting(
+      program,
+      mintAddress,
+      authority.publicKey,
+      beneficiary.publicKey,
+      1000,
+      {
+        startTime: now - 7200,
+        cliffTime: now - 3600,
+        endTime: now - 60,
+        revocable: false,
+      }
+    );
+
+    // 1% of the claim, capped at 5.
+    await setTransferFee(program, mintAddress, authority.publicKey, 100, 5);
+    await claimVested(program, vesting, beneficiaryTokenAccount, beneficiary);
+
+    const beneficiaryData = await program.account.tokenAccount.fetch(
+      beneficiaryTokenAccount
+    );
+    assert.equal(beneficiaryData.amount.toNumber(), 995);
+    assert.equal(beneficiaryData.withheldAmount.toNumber(), 5);
+  });
+
+  it("Should return unvested tokens to the grantor on revoke", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const beneficiary = Keypair.generate();
+    const grantorTokenAccount = await createTokenAccount(program, mintAddress, authority.publicKey, authority.publicKey);
+    const beneficiaryTokenAccount = await createTokenAccount(program, mintAddress, beneficiary.publicKey, authority.publicKey);
+    await mintTokens(program, mintAddress, grantorTokenAccount, authority.publicKey, 2000);
+
+    // Halfway through a schedule without a cliff.
+    const now = Math.floor(Date.now() / 1000);
+    const schedule = { startTime: now - 3600, cliffTime: now - 3600, endTime: now + 3600 };
+    const revocable = await createVesting(program, mintAddress, authority.publicKey, beneficiary.publicKey, 1000, {
+      ...schedule,
+      revocable: true,
+    });
+    const irrevocable = await createVesting(program, mintAddress, authority.publicKey, beneficiary.publicKey, 1000, {
+      ...schedule,
+      revocable: false,
+    });
+
+    await revokeVesting(program, revocable, grantorTokenAccount, authority.publicKey);
+
+    const vestingData = await program.account.vestingAccount.fetch(revocable);
+    const kept = vestingData.totalAmount.toNumber();
+    assert.isAbove(kept, 400);
+    assert.isBelow(kept, 600);
+    const grantorData = await program.account.tokenAccount.fetch(grantorTokenAccount);
+    assert.equal(grantorData.amount.toNumber(), 1000 - kept);
+
+    for (const vesting of [revocable, irrevocable]) {
+      try {
+        await revokeVesting(program, vesting, grantorTokenAccount, authority.publicKey);
+        assert.fail("Expected error was not thrown");
+      } catch (error) {
+        assert.include(error.toString(), "VestingNotRevocable");
+        console.log("Revoking a non-revocable schedule failed.");
+      }
+    }
+
+    // What vested before the revoke stays claimable, after which the schedule is closed.
+    await claimVested(program, revocable, beneficiaryTokenAccount, beneficiary);
+    const beneficiaryData = await program.account.tokenAccount.fetch(beneficiaryTokenAccount);
+    assert.equal(beneficiaryData.amount.toNumber(), kept);
+    assert.isNull(await program.account.vestingAccount.fetchNullable(revocable));
+  });
+
+  it("Should swap tokens of two mints through an escrowed offer", async () => {
+    const offerMint = await initializeMint(program, authority.publicKey, decimals);
+    const requestMint = await initializeMint(program, authority.publicKey, decimals);
+    const maker = Keypair.generate();
+    const taker = Keypair.generate();
+    const makerOfferAccount = await createTokenAccount(program, offerMint, maker.publicKey, authority.publicKey);
+    const makerRequestAccount = await createTokenAccount(program, requestMint, maker.publicKey, authority.publicKey);
+    const takerOfferAccount = await createTokenAccount(program, offerMint, taker.publicKey, authority.publicKey);
+    const takerRequestAccount = await createTokenAccount(program, requestMint, taker.publicKey, authority.publicKey);
+    await mintTokens(program, offerMint, makerOfferAccount, authority.publicKey, 1000);
+    await mintTokens(program, requestMint, takerRequestAccount, authority.publicKey, 150);
+
+    const offer = await makeOffer(program, maker, offerMint, 300, requestMint, 200, authority.publicKey);
+
+    let makerData = await program.account.tokenAccount.fetch(makerOfferAccount);
+    assert.equal(makerData.amount.toNumber(), 700);
+
+    try {
+      await takeOffer(program, offer, maker);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "SelfTransfer");
+      console.log("Taking one's own offer failed.");
+    }
+
+    try {
+      await takeOffer(program, offer, taker);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "InsufficientFunds");
+      console.log("Taking an offer without the requested tokens failed.");
+    }
+
+    await mintTokens(program, requestMint, takerRequestAccount, authority.publicKey, 50);
+    await takeOffer(program, offer, taker);
+
+    makerData = await program.account.tokenAccount.fetch(makerRequestAccount);
+    assert.equal(makerData.amount.toNumber(), 200);
+    let takerData = await program.account.tokenAccount.fetch(takerOfferAccount);
+    assert.equal(takerData.amount.toNumber(), 300);
+    takerData = await program.account.tokenAccount.fetch(takerRequestAccount);
+    assert.equal(takerData.amount.toNumber(), 0);
+    assert.isNull(await program.account.offer.fetchNullable(offer));
+  });
+
+  it("Should return escrowed tokens when an offer is cancelled", async () => {
+    const offerMint = await initializeMint(program, authority.publicKey, decimals);
+    const requestMint = await initializeMint(program, authority.publicKey, decimals);
+    const maker = Keypair.generate();
+    const taker = Keypair.generate();
+    const makerOfferAccount = await createTokenAccount(program, offerMint, maker.publicKey, authority.publicKey);
+    await createTokenAccount(program, requestMint, maker.publicKey, authority.publicKey);
+    await createTokenAccount(program, offerMint, taker.publicKey, authority.publicKey);
+    const takerRequestAccount = await createTokenAccount(program, requestMint, taker.publicKey, authority.publicKey);
+    await mintTokens(program, offerMint, makerOfferAccount, authority.publicKey, 1000);
+    await mintTokens(program, requestMint, takerRequestAccount, authority.publicKey, 200);
+
+    const offer = await makeOffer(program, maker, offerMint, 300, requestMint, 200, authority.publicKey);
+    await cancelOffer(program, offer, maker);
+
+    const makerData = await program.account.tokenAccount.fetch(makerOfferAccount);
+    assert.equal(makerData.amount.toNumber(), 1000);
+    assert.isNull(await program.account.offer.fetchNullable(offer));
+
+    try {
+      await takeOffer(program, offer, taker);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "Account does not exist");
+      console.log("Taking a cancelled offer failed.");
+    }
+  });
+
+  it("Should pay each airdrop recipient once against the merkle root", async () => {
+    const mintAddress = await initializeMint(program, authority.publicKey, decimals);
+    const funderAccount = await createTokenAccount(program, mintAddress, authority.publicKey, authority.publicKey);
+    await mintTokens(program, mintAddress, funderAccount, authority.publicKey, 1000);
+
+    const recipients = [100, 200, 300].map((amount) => ({
+      claimant: Keypair.generate().publicKey,
+      amount,
+    }));
+    const accounts = [];
+    for (const { claimant } of recipients) {
+      accounts.push(await createTokenAccount(program, mintAddress, claimant, authority.publicKey));
+    }
+
+    const { distributor, proofs } = await createDistributor(program, mintAddress, authority.publicKey, recipients);
+
+    let funderData = await program.account.tokenAccount.fetch(funderAccount);
+    assert.equal(funderData.amount.toNumber(), 400);
+
+    for (const [index, { claimant, amount }] of recipients.entries()) {
+      await claimAirdrop(program, distributor, index, claimant, amount, proofs[index]);
+      const data = await program.account.tokenAccount.fetch(accounts[index]);
+      assert.equal(data.amount.toNumber(), amount);
+    }
+
+    const distributorData = await program.account.distributor.fetch(distributor);
+    assert.equal(distributorData.claimedAmount.toNumber(), 600);
+
+    try {
+      await claimAirdrop(program, distributor, 1, recipients[1].claimant, 200, proofs[1]);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "AlreadyClaimed");
+      console.log("Claiming an airdrop twice failed.");
+    }
+  });
+
+  it("Should not claim an airdrop with a proof for another amount", async () => {
+    const mintAddress = await initializeMint(program, authority.publicKey, decimals);
+    const funderAccount = await createTokenAccount(program, mintAddress, authority.publicKey, authority.publicKey);
+    await mintTokens(program, mintAddress, funderAccount, authority.publicKey, 1000);
+
+    const recipients = [100, 200].map((amount) => ({
+      claimant: Keypair.generate().publicKey,
+      amount,
+    }));
+    const claimantAccount = await createTokenAccount(program, mintAddress, recipients[0].claimant, authority.publicKey);
+
+    const { distributor, proofs } = await createDistributor(program, mintAddress, authority.publicKey, recipients);
+
+    try {
+      await claimAirdrop(program, distributor, 0, recipients[0].claimant, 300, proofs[0]);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidMerkleProof");
+      console.log("Claiming more than the airdropped amount failed.");
+    }
+
+    // Another recipient's proof does not pay out to a different wallet either.
+    try {
+      await claimAirdrop(program, distributor, 1, recipients[0].claimant, 200, proofs[1]);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidMerkleProof");
+      console.log("Claiming another recipient's airdrop failed.");
+    }
+
+    const data = await program.account.tokenAccount.fetch(claimantAccount);
+    assert.equal(data.amount.toNumber(), 0);
+  });
+
+  it("Should charge the transfer fee on airdrop claims", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const funderAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      authority.publicKey,
+      authority.publicKey
+    );
+    await mintTokens(
+      program,
+      mintAddress,
+      funderAccount,
+      authority.publicKey,
+      1000
+    );
+
+    const recipients = [
+      { claimant: Keypair.generate().publicKey, amount: 300 },
+    ];
+    const claimantAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      recipients[0].claimant,
+      authority.publicKey
+    );
+    const { distributor, proofs } = await createDistributor(
+      program,
+      mintAddress,
+      authority.publicKey,
+      recipients
+    );
+
+    // 1% of the claim, capped at 5.
+    await setTransferFee(program, mintAddress, authority.publicKey, 100, 5);
+    await claimAirdrop(
+      program,
+      distributor,
+      0,
+      recipients[0].claimant,
+      300,
+      proofs[0]
+    );
+
+    const data = await program.account.tokenAccount.fetch(claimantAccount);
+    assert.equal(data.amount.toNumber(), 295);
+    assert.equal(data.withheldAmount.toNumber(), 5);
+  });
+
+  it("Should return unclaimed airdrop tokens to the funder once it ends", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const funderAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      authority.publicKey,
+      authority.publicKey
+    );
+    await mintTokens(
+      program,
+      mintAddress,
+      funderAccount,
+      authority.publicKey,
+      1000
+    );
+
+    const recipients = [100, 200].map((amount) => ({
+      claimant: Keypair.generate().publicKey,
+      amount,
+    }));
+    for (const { claimant } of recipients) {
+      await createTokenAccount(
+        program,
+        mintAddress,
+        claimant,
+        authority.publicKey
+      );
+    }
+
+    const { distributor, proofs } = await createDistributor(
+      program,
+      mintAddress,
+      authority.publicKey,
+      recipients,
+      4
+    );
+    await claimAirdrop(
+      program,
+      distributor,
+      0,
+      recipients[0].claimant,
+      100,
+      proofs[0]
+    );
+
+    try {
+      await closeDistributor(program, distributor, authority.publicKey);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "AirdropNotEnded");
+      console.log("Closing an airdrop before it ended failed.");
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, 5000));
+
+    try {
+      await claimAirdrop(
+        program,
+        distributor,
+        1,
+        recipients[1].claimant,
+        200,
+        proofs[1]
+      );
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "AirdropEnded");
+      console.log("Claiming an ended airdrop failed.");
+    }
+
+    await closeDistributor(program, distributor, authority.publicKey);
+
+    const funderData = await program.account.tokenAccount.fetch(funderAccount);
+    assert.equal(funderData.amount.toNumber(), 900);
+    assert.isNull(
+      await program.account.distributor.fetchNullable(distributor)
+    );
+  });
+
+  it("Should not close a distributor while its claim bitmaps are open", async () => {
+    const mintAddress = await initializeMint(
+      program,
+      authority.publicKey,
+      decimals
+    );
+    const funderAccount = await createTokenAccount(
+      program,
+      mintAddress,
+      authority.publicKey,
+      authority.publicKey
+    );
+    await mintTokens(
+      program,
+      mintAddress,
+      funderAccount,
+      authority.publicKey,
+      1000
+    );
+
+    const recipients = [
+      { claimant: Keypair.generate().publicKey, amount: 100 },
+    ];
+    const { distributor } = await createDistributor(
+      program,
+      mintAddress,
+      authority.publicKey,
+      recipients,
+      2
+    );
+    await new Promise((resolve) => setTimeout(resolve, 4000));
+
+    try {
+      await closeDistributor(program, distributor, authority.publicKey, false);
+      assert.fail("Expected error was not thrown");
+    } catch (error) {
+      assert.include(error.toString(), "ClaimBitmapsOpen");
+      console.log("Closing a distributor with open claim bitmaps failed.");
+    }
+
+    await closeDistributor(program, distributor, authority.publicKey);
+
+    const funderData = await program.account.tokenAccount.fetch(funderAccount);
+    assert.equal(funderData.amount.toNumber(), 1000);
+    assert.isNull(
+      await program.account.distributor.fetchNullable(distributor)
+    );
+  });
 });
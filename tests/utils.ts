@@ -1,7 +1,14 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, Wallet } from "@coral-xyz/anchor";
 import { SolanaToken } from "../target/types/solana_token";
-import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  Ed25519Program,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+} from "@solana/web3.js";
+import { createHash } from "crypto";
 
 export interface TestContext {
   provider: anchor.AnchorProvider;
@@ -24,15 +31,35 @@ export function setup(): TestContext {
   };
 }
 
+export interface MintOptions {
+  freezeAuthority?: PublicKey;
+  maxSupply?: number;
+  pauseAuthority?: PublicKey;
+  feeAuthority?: PublicKey;
+  nonTransferable?: boolean;
+  permanentDelegate?: PublicKey;
+  defaultFrozen?: boolean;
+}
+
 export async function initializeMint(
   program: Program<SolanaToken>,
   authority: PublicKey,
-  decimals: number = 9
+  decimals: number = 9,
+  options: MintOptions = {}
 ): Promise<PublicKey> {
   const mint = Keypair.generate();
 
   await program.methods
-    .initialize(decimals)
+    .initialize(
+      decimals,
+      options.freezeAuthority ?? null,
+      options.maxSupply !== undefined ? new anchor.BN(options.maxSupply) : null,
+      options.pauseAuthority ?? null,
+      options.feeAuthority ?? null,
+      options.nonTransferable ?? false,
+      options.permanentDelegate ?? null,
+      options.defaultFrozen ? { frozen: {} } : { initialized: {} }
+    )
     .accounts({
       mint: mint.publicKey,
       authority: authority,
@@ -70,20 +97,169 @@ export async function createTokenAccount(
   return pda;
 }
 
+export function denylistAddress(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  wallet: PublicKey
+): PublicKey {
+  const [pda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("deny"), mintAddress.toBuffer(), wallet.toBuffer()],
+    program.programId
+  );
+  return pda;
+}
+
+// Authorities may be multisigs, so the IDL does not mark them as signers. A keypair
+// signing as an authority or multisig member is passed as an extra signing account.
+export function signerMeta(signer: Keypair) {
+  return { pubkey: signer.publicKey, isWritable: false, isSigner: true };
+}
+
 export async function mintTokens(
   program: Program<SolanaToken>,
   mintAddress: PublicKey,
   tokenAccountAddress: PublicKey,
   authority: PublicKey,
+  amount: number,
+  multisigSigners: Keypair[] = []
+): Promise<void> {
+  const tokenData = await program.account.tokenAccount.fetch(tokenAccountAddress);
+
+  await program.methods
+    .mintTokens(new anchor.BN(amount))
+    .accounts({
+      mint: mintAddress,
+      tokenAccount: tokenAccountAddress,
+      authority: authority,
+      denyEntry: denylistAddress(program, mintAddress, tokenData.owner),
+      minterRole: null,
+    })
+    .remainingAccounts(multisigSigners.map(signerMeta))
+    .signers(multisigSigners)
+    .rpc();
+}
+
+export function minterRoleAddress(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  minter: PublicKey
+): PublicKey {
+  const [pda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("minter"), mintAddress.toBuffer(), minter.toBuffer()],
+    program.programId
+  );
+  return pda;
+}
+
+// Mints against the allowance of `minter` instead of as the mint authority.
+export async function mintAsMinter(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  tokenAccountAddress: PublicKey,
+  minter: Keypair,
   amount: number
 ): Promise<void> {
+  const tokenData = await program.account.tokenAccount.fetch(tokenAccountAddress);
+
   await program.methods
     .mintTokens(new anchor.BN(amount))
     .accounts({
       mint: mintAddress,
       tokenAccount: tokenAccountAddress,
+      authority: minter.publicKey,
+      denyEntry: denylistAddress(program, mintAddress, tokenData.owner),
+      minterRole: minterRoleAddress(program, mintAddress, minter.publicKey),
+    })
+    .remainingAccounts([signerMeta(minter)])
+    .signers([minter])
+    .rpc();
+}
+
+export async function addMinter(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  minter: PublicKey,
+  authority: PublicKey,
+  allowance: number
+): Promise<PublicKey> {
+  const pda = minterRoleAddress(program, mintAddress, minter);
+
+  await program.methods
+    .addMinter(new anchor.BN(allowance))
+    .accounts({
+      mint: mintAddress,
+      minterRole: pda,
+      minter: minter,
+      authority: authority,
+      payer: authority,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  return pda;
+}
+
+export async function setMinterAllowance(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  minter: PublicKey,
+  authority: PublicKey,
+  allowance: number
+): Promise<void> {
+  await program.methods
+    .setMinterAllowance(new anchor.BN(allowance))
+    .accounts({
+      mint: mintAddress,
+      minterRole: minterRoleAddress(program, mintAddress, minter),
+      authority: authority,
+    })
+    .rpc();
+}
+
+export async function removeMinter(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  minter: PublicKey,
+  authority: PublicKey
+): Promise<void> {
+  await program.methods
+    .removeMinter()
+    .accounts({
+      mint: mintAddress,
+      minterRole: minterRoleAddress(program, mintAddress, minter),
+      destination: authority,
+      authority: authority,
+    })
+    .rpc();
+}
+
+export async function mintToMany(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  authority: PublicKey,
+  recipients: { tokenAccount: PublicKey; amount: number }[]
+): Promise<void> {
+  // Each recipient is passed as its token account followed by its denylist entry.
+  const remainingAccounts = [];
+  for (const { tokenAccount } of recipients) {
+    const tokenData = await program.account.tokenAccount.fetch(tokenAccount);
+    remainingAccounts.push(
+      { pubkey: tokenAccount, isWritable: true, isSigner: false },
+      {
+        pubkey: denylistAddress(program, mintAddress, tokenData.owner),
+        isWritable: false,
+        isSigner: false,
+      }
+    );
+  }
+
+  await program.methods
+    .mintToMany(recipients.map(({ amount }) => new anchor.BN(amount)))
+    .accounts({
+      mint: mintAddress,
       authority: authority,
     })
+    .remainingAccounts(remainingAccounts)
     .rpc();
 }
 
@@ -91,17 +267,63 @@ export async function transferTokens(
   program: Program<SolanaToken>,
   fromTokenAccount: PublicKey,
   toTokenAccount: PublicKey,
-  owner: Keypair,
+  authority: Keypair,
   amount: number
 ): Promise<void> {
+  const fromData = await program.account.tokenAccount.fetch(fromTokenAccount);
+  // Destinations that are not token accounts are rejected before the denylist is checked.
+  const toData = await program.account.tokenAccount
+    .fetchNullable(toTokenAccount)
+    .catch(() => null);
+  const toOwner = toData?.owner ?? toTokenAccount;
+
   await program.methods
     .transfer(new anchor.BN(amount))
     .accounts({
+      mint: fromData.mint,
       from: fromTokenAccount,
       to: toTokenAccount,
-      owner: owner.publicKey,
+      authority: authority.publicKey,
+      fromDenyEntry: denylistAddress(program, fromData.mint, fromData.owner),
+      toDenyEntry: denylistAddress(program, fromData.mint, toOwner),
     })
-    .signers([owner])
+    .remainingAccounts([signerMeta(authority)])
+    .signers([authority])
+    .rpc();
+}
+
+export async function transferMany(
+  program: Program<SolanaToken>,
+  fromTokenAccount: PublicKey,
+  authority: Keypair,
+  recipients: { tokenAccount: PublicKey; amount: number }[]
+): Promise<void> {
+  const fromData = await program.account.tokenAccount.fetch(fromTokenAccount);
+
+  // Each recipient is passed as its token account followed by its denylist entry.
+  const remainingAccounts = [];
+  for (const { tokenAccount } of recipients) {
+    const toData = await program.account.tokenAccount.fetch(tokenAccount);
+    remainingAccounts.push(
+      { pubkey: tokenAccount, isWritable: true, isSigner: false },
+      {
+        pubkey: denylistAddress(program, fromData.mint, toData.owner),
+        isWritable: false,
+        isSigner: false,
+      }
+    );
+  }
+
+  await program.methods
+    .transferMany(recipients.map(({ amount }) => new anchor.BN(amount)))
+    .accounts({
+      mint: fromData.mint,
+      from: fromTokenAccount,
+      authority: authority.publicKey,
+      fromDenyEntry: denylistAddress(program, fromData.mint, fromData.owner),
+    })
+    .remainingAccounts([...remainingAccounts, signerMeta(authority)])
+    .signers([authority])
     .rpc();
 }
 
@@ -109,16 +331,1114 @@ export async function burnTokens(
   program: Program<SolanaToken>,
   mintAddress: PublicKey,
   tokenAccountAddress: PublicKey,
-  owner: Keypair,
+  authority: Keypair,
   amount: number
 ): Promise<void> {
+  const tokenData = await program.account.tokenAccount.fetch(tokenAccountAddress);
+
   await program.methods
     .burn(new anchor.BN(amount))
     .accounts({
       mint: mintAddress,
       tokenAccount: tokenAccountAddress,
+      authority: authority.publicKey,
+      denyEntry: denylistAddress(program, mintAddress, tokenData.owner),
+    })
+    .remainingAccounts([signerMeta(authority)])
+    .signers([authority])
+    .rpc();
+}
+
+export async function setMintAuthority(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  authority: PublicKey,
+  newAuthority: PublicKey
+): Promise<void> {
+  await program.methods
+    .setMintAuthority(newAuthority)
+    .accounts({
+      mint: mintAddress,
+      authority: authority,
+    })
+    .rpc();
+}
+
+export async function revokeMintAuthority(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  authority: PublicKey
+): Promise<void> {
+  await program.methods
+    .revokeMintAuthority()
+    .accounts({
+      mint: mintAddress,
+      authority: authority,
+    })
+    .rpc();
+}
+
+export async function proposeMintAuthority(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  authority: PublicKey,
+  newAuthority: PublicKey
+): Promise<void> {
+  await program.methods
+    .proposeMintAuthority(newAuthority)
+    .accounts({
+      mint: mintAddress,
+      authority: authority,
+    })
+    .rpc();
+}
+
+export async function acceptMintAuthority(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  pendingAuthority: Keypair
+): Promise<void> {
+  await program.methods
+    .acceptMintAuthority()
+    .accounts({
+      mint: mintAddress,
+      pendingAuthority: pendingAuthority.publicKey,
+    })
+    .remainingAccounts([signerMeta(pendingAuthority)])
+    .signers([pendingAuthority])
+    .rpc();
+}
+
+export async function cancelMintAuthorityTransfer(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  authority: PublicKey
+): Promise<void> {
+  await program.methods
+    .cancelMintAuthorityTransfer()
+    .accounts({
+      mint: mintAddress,
+      authority: authority,
+    })
+    .rpc();
+}
+
+export async function freezeAccount(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  tokenAccountAddress: PublicKey,
+  freezeAuthority: PublicKey
+): Promise<void> {
+  await program.methods
+    .freezeAccount()
+    .accounts({
+      mint: mintAddress,
+      tokenAccount: tokenAccountAddress,
+      freezeAuthority: freezeAuthority,
+    })
+    .rpc();
+}
+
+export async function thawAccount(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  tokenAccountAddress: PublicKey,
+  freezeAuthority: PublicKey
+): Promise<void> {
+  await program.methods
+    .thawAccount()
+    .accounts({
+      mint: mintAddress,
+      tokenAccount: tokenAccountAddress,
+      freezeAuthority: freezeAuthority,
+    })
+    .rpc();
+}
+
+export async function approve(
+  program: Program<SolanaToken>,
+  tokenAccountAddress: PublicKey,
+  owner: Keypair,
+  delegate: PublicKey,
+  amount: number
+): Promise<void> {
+  await program.methods
+    .approve(new anchor.BN(amount))
+    .accounts({
+      tokenAccount: tokenAccountAddress,
+      delegate: delegate,
+      owner: owner.publicKey,
+    })
+    .remainingAccounts([signerMeta(owner)])
+    .signers([owner])
+    .rpc();
+}
+
+export async function revoke(
+  program: Program<SolanaToken>,
+  tokenAccountAddress: PublicKey,
+  owner: Keypair
+): Promise<void> {
+  await program.methods
+    .revoke()
+    .accounts({
+      tokenAccount: tokenAccountAddress,
+      owner: owner.publicKey,
+    })
+    .remainingAccounts([signerMeta(owner)])
+    .signers([owner])
+    .rpc();
+}
+
+export function permitNonceAddress(
+  program: Program<SolanaToken>,
+  owner: PublicKey,
+  mintAddress: PublicKey,
+  nonce: number
+): PublicKey {
+  const [pda] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("permit"),
+      owner.toBuffer(),
+      mintAddress.toBuffer(),
+      new anchor.BN(nonce).toArrayLike(Buffer, "le", 8),
+    ],
+    program.programId
+  );
+  return pda;
+}
+
+// Mirrors `permit_message` in the program.
+export function permitMessage(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  owner: PublicKey,
+  spender: PublicKey,
+  amount: number,
+  nonce: number,
+  deadline: number
+): Buffer {
+  return Buffer.concat([
+    Buffer.from("solana-token:permit"),
+    program.programId.toBuffer(),
+    mintAddress.toBuffer(),
+    owner.toBuffer(),
+    spender.toBuffer(),
+    new anchor.BN(amount).toArrayLike(Buffer, "le", 8),
+    new anchor.BN(nonce).toArrayLike(Buffer, "le", 8),
+    new anchor.BN(deadline).toArrayLike(Buffer, "le", 8),
+  ]);
+}
+
+// The owner only signs the permit; the provider wallet submits it and pays.
+// `signer` stands in for the owner to produce an invalid signature.
+export async function permit(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  tokenAccountAddress: PublicKey,
+  owner: Keypair,
+  spender: PublicKey,
+  amount: number,
+  nonce: number,
+  deadline: number,
+  payer: PublicKey,
+  signer: Keypair = owner
+): Promise<void> {
+  const message = permitMessage(
+    program,
+    mintAddress,
+    owner.publicKey,
+    spender,
+    amount,
+    nonce,
+    deadline
+  );
+
+  await program.methods
+    .permit(
+      spender,
+      new anchor.BN(amount),
+      new anchor.BN(nonce),
+      new anchor.BN(deadline)
+    )
+    .accounts({
+      tokenAccount: tokenAccountAddress,
+      permitNonce: permitNonceAddress(
+        program,
+        owner.publicKey,
+        mintAddress,
+        nonce
+      ),
+      instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      payer: payer,
+      systemProgram: SystemProgram.programId,
+    })
+    .preInstructions([
+      Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: signer.secretKey,
+        message,
+      }),
+    ])
+    .rpc();
+}
+
+export async function cancelPermit(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  owner: Keypair,
+  nonce: number,
+  payer: PublicKey
+): Promise<void> {
+  await program.methods
+    .cancelPermit(new anchor.BN(nonce))
+    .accounts({
+      mint: mintAddress,
+      permitNonce: permitNonceAddress(
+        program,
+        owner.publicKey,
+        mintAddress,
+        nonce
+      ),
       owner: owner.publicKey,
+      payer: payer,
+      systemProgram: SystemProgram.programId,
     })
+    .remainingAccounts([signerMeta(owner)])
     .signers([owner])
     .rpc();
 }
+
+export async function closeTokenAccount(
+  program: Program<SolanaToken>,
+  tokenAccountAddress: PublicKey,
+  owner: Keypair,
+  destination: PublicKey
+): Promise<void> {
+  const { mint } = await program.account.tokenAccount.fetch(
+    tokenAccountAddress
+  );
+
+  await program.methods
+    .closeTokenAccount()
+    .accounts({
+      mint: mint,
+      tokenAccount: tokenAccountAddress,
+      destination: destination,
+      owner: owner.publicKey,
+    })
+    .remainingAccounts([signerMeta(owner)])
+    .signers([owner])
+    .rpc();
+}
+
+export async function pause(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  pauseAuthority: PublicKey
+): Promise<void> {
+  await program.methods
+    .pause()
+    .accounts({
+      mint: mintAddress,
+      pauseAuthority: pauseAuthority,
+    })
+    .rpc();
+}
+
+export async function unpause(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  pauseAuthority: PublicKey
+): Promise<void> {
+  await program.methods
+    .unpause()
+    .accounts({
+      mint: mintAddress,
+      pauseAuthority: pauseAuthority,
+    })
+    .rpc();
+}
+
+export async function initializeMetadata(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  authority: PublicKey,
+  name: string,
+  symbol: string,
+  uri: string,
+  multisigSigners: Keypair[] = []
+): Promise<PublicKey> {
+  // Derive PDA
+  const [pda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), mintAddress.toBuffer()],
+    program.programId
+  );
+
+  await program.methods
+    .initializeMetadata(name, symbol, uri)
+    .accounts({
+      mint: mintAddress,
+      metadata: pda,
+      authority: authority,
+      payer: program.provider.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .remainingAccounts(multisigSigners.map(signerMeta))
+    .signers(multisigSigners)
+    .rpc();
+
+  return pda;
+}
+
+export async function updateMetadata(
+  program: Program<SolanaToken>,
+  metadataAddress: PublicKey,
+  updateAuthority: Keypair,
+  fields: { name?: string; symbol?: string; uri?: string }
+): Promise<void> {
+  await program.methods
+    .updateMetadata(fields.name ?? null, fields.symbol ?? null, fields.uri ?? null)
+    .accounts({
+      metadata: metadataAddress,
+      updateAuthority: updateAuthority.publicKey,
+    })
+    .remainingAccounts([signerMeta(updateAuthority)])
+    .signers([updateAuthority])
+    .rpc();
+}
+
+export async function setMetadataField(
+  program: Program<SolanaToken>,
+  metadataAddress: PublicKey,
+  updateAuthority: PublicKey,
+  key: string,
+  value: string,
+  multisigSigners: Keypair[] = []
+): Promise<void> {
+  await program.methods
+    .setMetadataField(key, value)
+    .accounts({
+      metadata: metadataAddress,
+      updateAuthority: updateAuthority,
+    })
+    .remainingAccounts(multisigSigners.map(signerMeta))
+    .signers(multisigSigners)
+    .rpc();
+}
+
+export async function removeMetadataField(
+  program: Program<SolanaToken>,
+  metadataAddress: PublicKey,
+  updateAuthority: PublicKey,
+  key: string,
+  multisigSigners: Keypair[] = []
+): Promise<void> {
+  await program.methods
+    .removeMetadataField(key)
+    .accounts({
+      metadata: metadataAddress,
+      updateAuthority: updateAuthority,
+    })
+    .remainingAccounts(multisigSigners.map(signerMeta))
+    .signers(multisigSigners)
+    .rpc();
+}
+
+export async function setMetadataUpdateAuthority(
+  program: Program<SolanaToken>,
+  metadataAddress: PublicKey,
+  updateAuthority: PublicKey,
+  newAuthority: PublicKey | null,
+  multisigSigners: Keypair[] = []
+): Promise<void> {
+  await program.methods
+    .setMetadataUpdateAuthority(newAuthority)
+    .accounts({
+      metadata: metadataAddress,
+      updateAuthority: updateAuthority,
+    })
+    .remainingAccounts(multisigSigners.map(signerMeta))
+    .signers(multisigSigners)
+    .rpc();
+}
+
+export async function setTransferFee(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  feeAuthority: PublicKey,
+  basisPoints: number,
+  maximumFee: number
+): Promise<void> {
+  await program.methods
+    .setTransferFee(basisPoints, new anchor.BN(maximumFee))
+    .accounts({
+      mint: mintAddress,
+      feeAuthority: feeAuthority,
+    })
+    .rpc();
+}
+
+export async function harvestWithheld(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  tokenAccountAddress: PublicKey,
+  feeAuthority: PublicKey
+): Promise<void> {
+  await program.methods
+    .harvestWithheld()
+    .accounts({
+      mint: mintAddress,
+      tokenAccount: tokenAccountAddress,
+      feeAuthority: feeAuthority,
+    })
+    .rpc();
+}
+
+export async function withdrawWithheld(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  destinationAddress: PublicKey,
+  feeAuthority: PublicKey
+): Promise<void> {
+  await program.methods
+    .withdrawWithheld()
+    .accounts({
+      mint: mintAddress,
+      destination: destinationAddress,
+      feeAuthority: feeAuthority,
+    })
+    .rpc();
+}
+
+export async function addToDenylist(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  wallet: PublicKey,
+  freezeAuthority: PublicKey
+): Promise<PublicKey> {
+  const pda = denylistAddress(program, mintAddress, wallet);
+
+  await program.methods
+    .addToDenylist()
+    .accounts({
+      mint: mintAddress,
+      denyEntry: pda,
+      wallet: wallet,
+      freezeAuthority: freezeAuthority,
+      payer: freezeAuthority,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  return pda;
+}
+
+export async function removeFromDenylist(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  wallet: PublicKey,
+  freezeAuthority: PublicKey
+): Promise<void> {
+  await program.methods
+    .removeFromDenylist()
+    .accounts({
+      mint: mintAddress,
+      denyEntry: denylistAddress(program, mintAddress, wallet),
+      destination: freezeAuthority,
+      freezeAuthority: freezeAuthority,
+    })
+    .rpc();
+}
+
+export async function createMultisig(
+  program: Program<SolanaToken>,
+  threshold: number,
+  signers: PublicKey[],
+  payer: PublicKey
+): Promise<PublicKey> {
+  const multisig = Keypair.generate();
+
+  await program.methods
+    .createMultisig(threshold, signers)
+    .accounts({
+      multisig: multisig.publicKey,
+      payer: payer,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([multisig])
+    .rpc();
+
+  return multisig.publicKey;
+}
+
+export function pendingMintAddress(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  id: number
+): PublicKey {
+  const [pda] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("pending_mint"),
+      mintAddress.toBuffer(),
+      new anchor.BN(id).toArrayLike(Buffer, "le", 8),
+    ],
+    program.programId
+  );
+  return pda;
+}
+
+export async function setMintDelay(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  authority: PublicKey,
+  delay: number
+): Promise<void> {
+  await program.methods
+    .setMintDelay(new anchor.BN(delay))
+    .accounts({
+      mint: mintAddress,
+      authority: authority,
+    })
+    .rpc();
+}
+
+export async function applyMintDelay(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  authority: PublicKey
+): Promise<void> {
+  await program.methods
+    .applyMintDelay()
+    .accounts({
+      mint: mintAddress,
+      authority: authority,
+    })
+    .rpc();
+}
+
+export async function setMintRateLimit(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  authority: PublicKey,
+  limit: number,
+  window: number
+): Promise<void> {
+  await program.methods
+    .setMintRateLimit(new anchor.BN(limit), new anchor.BN(window))
+    .accounts({
+      mint: mintAddress,
+      authority: authority,
+    })
+    .rpc();
+}
+
+export async function queueMint(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  tokenAccountAddress: PublicKey,
+  authority: PublicKey,
+  amount: number
+): Promise<PublicKey> {
+  const mintData = await program.account.tokenMint.fetch(mintAddress);
+  const pda = pendingMintAddress(
+    program,
+    mintAddress,
+    mintData.queuedMintCount.toNumber()
+  );
+
+  await program.methods
+    .queueMint(new anchor.BN(amount))
+    .accounts({
+      mint: mintAddress,
+      tokenAccount: tokenAccountAddress,
+      pendingMint: pda,
+      authority: authority,
+      payer: authority,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  return pda;
+}
+
+export async function executeMint(
+  program: Program<SolanaToken>,
+  pendingMintAddress: PublicKey,
+  authority: PublicKey
+): Promise<void> {
+  const pendingMint = await program.account.pendingMint.fetch(pendingMintAddress);
+  const tokenData = await program.account.tokenAccount.fetch(pendingMint.tokenAccount);
+
+  await program.methods
+    .executeMint()
+    .accounts({
+      mint: pendingMint.mint,
+      pendingMint: pendingMintAddress,
+      tokenAccount: pendingMint.tokenAccount,
+      denyEntry: denylistAddress(program, pendingMint.mint, tokenData.owner),
+      destination: authority,
+      authority: authority,
+    })
+    .rpc();
+}
+
+export async function cancelMint(
+  program: Program<SolanaToken>,
+  pendingMintAddress: PublicKey,
+  authority: PublicKey
+): Promise<void> {
+  const pendingMint = await program.account.pendingMint.fetch(pendingMintAddress);
+
+  await program.methods
+    .cancelMint()
+    .accounts({
+      mint: pendingMint.mint,
+      pendingMint: pendingMintAddress,
+      destination: authority,
+      authority: authority,
+    })
+    .rpc();
+}
+
+export function vestingAddress(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  id: number
+): PublicKey {
+  const [pda] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("vesting"),
+      mintAddress.toBuffer(),
+      new anchor.BN(id).toArrayLike(Buffer, "le", 8),
+    ],
+    program.programId
+  );
+  return pda;
+}
+
+export interface VestingSchedule {
+  startTime: number;
+  cliffTime: number;
+  endTime: number;
+  revocable: boolean;
+}
+
+// The grantor is the provider wallet, vesting from its own token account.
+export async function createVesting(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  grantor: PublicKey,
+  beneficiary: PublicKey,
+  amount: number,
+  schedule: VestingSchedule
+): Promise<PublicKey> {
+  const mintData = await program.account.tokenMint.fetch(mintAddress);
+  const pda = vestingAddress(
+    program,
+    mintAddress,
+    mintData.vestingCount.toNumber()
+  );
+  const [source] = PublicKey.findProgramAddressSync(
+    [Buffer.from("token"), grantor.toBuffer(), mintAddress.toBuffer()],
+    program.programId
+  );
+
+  await program.methods
+    .createVesting(
+      new anchor.BN(amount),
+      new anchor.BN(schedule.startTime),
+      new anchor.BN(schedule.cliffTime),
+      new anchor.BN(schedule.endTime),
+      schedule.revocable
+    )
+    .accounts({
+      mint: mintAddress,
+      source: source,
+      vesting: pda,
+      beneficiary: beneficiary,
+      denyEntry: denylistAddress(program, mintAddress, grantor),
+      grantor: grantor,
+      payer: grantor,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  return pda;
+}
+
+export async function claimVested(
+  program: Program<SolanaToken>,
+  vestingAddress: PublicKey,
+  tokenAccountAddress: PublicKey,
+  beneficiary: Keypair
+): Promise<void> {
+  const vesting = await program.account.vestingAccount.fetch(vestingAddress);
+
+  await program.methods
+    .claimVested()
+    .accounts({
+      mint: vesting.mint,
+      vesting: vestingAddress,
+      tokenAccount: tokenAccountAddress,
+      denyEntry: denylistAddress(program, vesting.mint, beneficiary.publicKey),
+      beneficiary: beneficiary.publicKey,
+      grantor: vesting.grantor,
+    })
+    .remainingAccounts([signerMeta(beneficiary)])
+    .signers([beneficiary])
+    .rpc();
+}
+
+export async function revokeVesting(
+  program: Program<SolanaToken>,
+  vestingAddress: PublicKey,
+  tokenAccountAddress: PublicKey,
+  grantor: PublicKey
+): Promise<void> {
+  const vesting = await program.account.vestingAccount.fetch(vestingAddress);
+
+  await program.methods
+    .revokeVesting()
+    .accounts({
+      mint: vesting.mint,
+      vesting: vestingAddress,
+      tokenAccount: tokenAccountAddress,
+      denyEntry: denylistAddress(program, vesting.mint, grantor),
+      grantor: grantor,
+    })
+    .rpc();
+}
+
+export function offerAddress(
+  program: Program<SolanaToken>,
+  maker: PublicKey,
+  id: number
+): PublicKey {
+  const [pda] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("offer"),
+      maker.toBuffer(),
+      new anchor.BN(id).toArrayLike(Buffer, "le", 8),
+    ],
+    program.programId
+  );
+  return pda;
+}
+
+function tokenAccountPda(
+  program: Program<SolanaToken>,
+  owner: PublicKey,
+  mintAddress: PublicKey
+): PublicKey {
+  const [pda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("token"), owner.toBuffer(), mintAddress.toBuffer()],
+    program.programId
+  );
+  return pda;
+}
+
+export async function makeOffer(
+  program: Program<SolanaToken>,
+  maker: Keypair,
+  offerMint: PublicKey,
+  offerAmount: number,
+  requestMint: PublicKey,
+  requestAmount: number,
+  payer: PublicKey
+): Promise<PublicKey> {
+  const id = Date.now();
+  const offer = offerAddress(program, maker.publicKey, id);
+
+  await program.methods
+    .makeOffer(
+      new anchor.BN(id),
+      new anchor.BN(offerAmount),
+      new anchor.BN(requestAmount)
+    )
+    .accounts({
+      offerMint: offerMint,
+      requestMint: requestMint,
+      source: tokenAccountPda(program, maker.publicKey, offerMint),
+      offer: offer,
+      escrow: tokenAccountPda(program, offer, offerMint),
+      denyEntry: denylistAddress(program, offerMint, maker.publicKey),
+      maker: maker.publicKey,
+      payer: payer,
+      systemProgram: SystemProgram.programId,
+    })
+    .remainingAccounts([signerMeta(maker)])
+    .signers([maker])
+    .rpc();
+
+  return offer;
+}
+
+export async function takeOffer(
+  program: Program<SolanaToken>,
+  offer: PublicKey,
+  taker: Keypair
+): Promise<void> {
+  const { maker, offerMint, requestMint } = await program.account.offer.fetch(offer);
+  const owner = taker.publicKey;
+
+  await program.methods
+    .takeOffer()
+    .accounts({
+      offer: offer,
+      offerMint: offerMint,
+      requestMint: requestMint,
+      escrow: tokenAccountPda(program, offer, offerMint),
+      makerOfferAccount: tokenAccountPda(program, maker, offerMint),
+      makerRequestAccount: tokenAccountPda(program, maker, requestMint),
+      takerOfferAccount: tokenAccountPda(program, owner, offerMint),
+      takerRequestAccount: tokenAccountPda(program, owner, requestMint),
+      makerOfferDenyEntry: denylistAddress(program, offerMint, maker),
+      makerRequestDenyEntry: denylistAddress(program, requestMint, maker),
+      takerOfferDenyEntry: denylistAddress(program, offerMint, owner),
+      takerRequestDenyEntry: denylistAddress(program, requestMint, owner),
+      maker: maker,
+      taker: owner,
+    })
+    .remainingAccounts([signerMeta(taker)])
+    .signers([taker])
+    .rpc();
+}
+
+export async function cancelOffer(
+  program: Program<SolanaToken>,
+  offer: PublicKey,
+  maker: Keypair
+): Promise<void> {
+  const { offerMint } = await program.account.offer.fetch(offer);
+
+  await program.methods
+    .cancelOffer()
+    .accounts({
+      offer: offer,
+      offerMint: offerMint,
+      escrow: tokenAccountPda(program, offer, offerMint),
+      tokenAccount: tokenAccountPda(program, maker.publicKey, offerMint),
+      denyEntry: denylistAddress(program, offerMint, maker.publicKey),
+      maker: maker.publicKey,
+    })
+    .remainingAccounts([signerMeta(maker)])
+    .signers([maker])
+    .rpc();
+}
+
+// Mirrors CLAIMS_PER_BITMAP in the program.
+const CLAIMS_PER_BITMAP = 8192 * 8;
+
+export interface AirdropRecipient {
+  claimant: PublicKey;
+  amount: number;
+}
+
+export function distributorAddress(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  merkleRoot: Buffer
+): PublicKey {
+  const [pda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("distributor"), mintAddress.toBuffer(), merkleRoot],
+    program.programId
+  );
+  return pda;
+}
+
+export function claimBitmapAddress(
+  program: Program<SolanaToken>,
+  distributor: PublicKey,
+  chunk: number
+): PublicKey {
+  const [pda] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("claims"),
+      distributor.toBuffer(),
+      new anchor.BN(chunk).toArrayLike(Buffer, "le", 8),
+    ],
+    program.programId
+  );
+  return pda;
+}
+
+export function airdropLeaf(
+  index: number,
+  claimant: PublicKey,
+  amount: number
+): Buffer {
+  return createHash("sha256")
+    .update(Buffer.from([0]))
+    .update(new anchor.BN(index).toArrayLike(Buffer, "le", 8))
+    .update(claimant.toBuffer())
+    .update(new anchor.BN(amount).toArrayLike(Buffer, "le", 8))
+    .digest();
+}
+
+function merkleParent(a: Buffer, b: Buffer): Buffer {
+  const [left, right] = Buffer.compare(a, b) <= 0 ? [a, b] : [b, a];
+  return createHash("sha256")
+    .update(Buffer.from([1]))
+    .update(left)
+    .update(right)
+    .digest();
+}
+
+// Same tree as the CLI's airdrop-build: the last node of an odd level moves up unchanged.
+export function buildMerkleTree(recipients: AirdropRecipient[]): {
+  root: Buffer;
+  proofs: Buffer[][];
+} {
+  let level = recipients.map(({ claimant, amount }, index) =>
+    airdropLeaf(index, claimant, amount)
+  );
+  const proofs: Buffer[][] = recipients.map(() => []);
+  const positions = recipients.map((_, index) => index);
+
+  while (level.length > 1) {
+    positions.forEach((position, leaf) => {
+      const sibling = level[position ^ 1];
+      if (sibling) {
+        proofs[leaf].push(sibling);
+      }
+      positions[leaf] = Math.floor(position / 2);
+    });
+
+    const next: Buffer[] = [];
+    for (let i = 0; i < level.length; i += 2) {
+      next.push(
+        i + 1 < level.length ? merkleParent(level[i], level[i + 1]) : level[i]
+      );
+    }
+    level = next;
+  }
+
+  return { root: level[0], proofs };
+}
+
+// The funder is the provider wallet, funding the vault from its own token account.
+export async function createDistributor(
+  program: Program<SolanaToken>,
+  mintAddress: PublicKey,
+  funder: PublicKey,
+  recipients: AirdropRecipient[],
+  durationSeconds: number = 3600
+): Promise<{ distributor: PublicKey; proofs: Buffer[][] }> {
+  const { root, proofs } = buildMerkleTree(recipients);
+  const distributor = distributorAddress(program, mintAddress, root);
+  const totalAmount = recipients.reduce((sum, { amount }) => sum + amount, 0);
+  const endTime = Math.floor(Date.now() / 1000) + durationSeconds;
+
+  await program.methods
+    .createDistributor(
+      Array.from(root),
+      new anchor.BN(recipients.length),
+      new anchor.BN(totalAmount),
+      new anchor.BN(endTime)
+    )
+    .accounts({
+      mint: mintAddress,
+      source: tokenAccountPda(program, funder, mintAddress),
+      distributor: distributor,
+      vault: tokenAccountPda(program, distributor, mintAddress),
+      denyEntry: denylistAddress(program, mintAddress, funder),
+      funder: funder,
+      payer: funder,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  const chunks = Math.ceil(recipients.length / CLAIMS_PER_BITMAP);
+  for (let chunk = 0; chunk < chunks; chunk++) {
+    await program.methods
+      .createClaimBitmap(new anchor.BN(chunk))
+      .accounts({
+        distributor: distributor,
+        claimBitmap: claimBitmapAddress(program, distributor, chunk),
+        payer: funder,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  return { distributor, proofs };
+}
+
+// Claims need no signature from the claimant; the provider wallet submits them.
+export async function claimAirdrop(
+  program: Program<SolanaToken>,
+  distributor: PublicKey,
+  index: number,
+  claimant: PublicKey,
+  amount: number,
+  proof: Buffer[]
+): Promise<void> {
+  const { mint } = await program.account.distributor.fetch(distributor);
+
+  await program.methods
+    .claim(
+      new anchor.BN(index),
+      new anchor.BN(amount),
+      proof.map((node) => Array.from(node))
+    )
+    .accounts({
+      mint: mint,
+      distributor: distributor,
+      vault: tokenAccountPda(program, distributor, mint),
+      claimBitmap: claimBitmapAddress(
+        program,
+        distributor,
+        Math.floor(index / CLAIMS_PER_BITMAP)
+      ),
+      tokenAccount: tokenAccountPda(program, claimant, mint),
+      denyEntry: denylistAddress(program, mint, claimant),
+    })
+    .rpc();
+}
+
+export async function closeClaimBitmap(
+  program: Program<SolanaToken>,
+  distributor: PublicKey,
+  chunk: number,
+  funder: PublicKey
+): Promise<void> {
+  await program.methods
+    .closeClaimBitmap(new anchor.BN(chunk))
+    .accounts({
+      distributor: distributor,
+      claimBitmap: claimBitmapAddress(program, distributor, chunk),
+      funder: funder,
+    })
+    .rpc();
+}
+
+// Pass `closeBitmaps = false` to leave the claim bitmaps open, which the program rejects.
+export async function closeDistributor(
+  program: Program<SolanaToken>,
+  distributor: PublicKey,
+  funder: PublicKey,
+  closeBitmaps: boolean = true
+): Promise<void> {
+  const { mint, numClaims } = await program.account.distributor.fetch(
+    distributor
+  );
+
+  if (closeBitmaps) {
+    const chunks = Math.ceil(numClaims.toNumber() / CLAIMS_PER_BITMAP);
+    for (let chunk = 0; chunk < chunks; chunk++) {
+      await closeClaimBitmap(program, distributor, chunk, funder);
+    }
+  }
+
+  await program.methods
+    .closeDistributor()
+    .accounts({
+      mint: mint,
+      distributor: distributor,
+      vault: tokenAccountPda(program, distributor, mint),
+      tokenAccount: tokenAccountPda(program, funder, mint),
+      denyEntry: denylistAddress(program, mint, funder),
+      funder: funder,
+    })
+    .rpc();
+}